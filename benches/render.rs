@@ -0,0 +1,134 @@
+//! How long it takes to generate the documentation for a large schema.
+//!
+//! The schema is generated rather than checked in: a thousand interconnected object types and as
+//! many input objects, a little larger than the GitHub v4 schema, so that anything that scales with
+//! the square of the schema's size shows up clearly.
+//!
+//! The `uses` group compares finding and serializing the uses of every type by scanning the whole
+//! schema for each page, as the pages used to, with looking them up in a `UseIndex`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use docql::{GraphqlRequest, Runtime};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How many object types the generated schema has
+const TYPES: usize = 1000;
+/// How many fields each of those types has
+const FIELDS: usize = 15;
+
+/// A runtime that reads the schema from memory and throws the output away
+struct BenchRuntime {
+    sdl: String,
+}
+
+#[async_trait::async_trait(?Send)]
+impl Runtime for BenchRuntime {
+    type Error = String;
+
+    async fn date(&self) -> Result<String, Self::Error> {
+        Ok("2021-01-01".to_string())
+    }
+
+    async fn get_args(&self) -> Result<Vec<String>, Self::Error> {
+        Ok(vec![
+            "--sdl".to_string(),
+            "schema.graphql".to_string(),
+            "--output".to_string(),
+            "out".to_string(),
+        ])
+    }
+
+    async fn query(
+        &self,
+        _url: &str,
+        _graphql: &GraphqlRequest,
+        _headers: HashMap<String, String>,
+    ) -> Result<Value, Self::Error> {
+        Err("The benchmark doesn't query endpoints".to_string())
+    }
+
+    async fn read_file(&self, _path: &str) -> Result<String, Self::Error> {
+        Ok(self.sdl.clone())
+    }
+
+    async fn read_directory(&self, _path: &str) -> Result<Vec<String>, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    async fn prepare_output_directory(&self, _output: &str) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn print(&self, _text: &str) {}
+
+    async fn write_file(
+        &self,
+        _output: &str,
+        _file: &str,
+        _contents: &str,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A schema where every type refers to several others, as fields and as arguments
+fn large_schema() -> String {
+    let mut sdl = String::from("type Query {\n");
+    for i in 0..TYPES {
+        sdl.push_str(&format!("  type{}: Type{}\n", i, i));
+    }
+    sdl.push_str("}\n\n");
+
+    for i in 0..TYPES {
+        sdl.push_str(&format!(
+            "\"\"\"Type number {}\"\"\"\ntype Type{} {{\n",
+            i, i
+        ));
+        for j in 0..FIELDS {
+            sdl.push_str(&format!(
+                "  \"\"\"Field number {}\"\"\"\n  field{}(filter: Input{}): [Type{}!]\n",
+                j,
+                j,
+                (i + j) % TYPES,
+                (i * 7 + j * 13) % TYPES
+            ));
+        }
+        sdl.push_str("}\n\n");
+        sdl.push_str(&format!("input Input{} {{ id: ID name: String }}\n\n", i));
+    }
+    sdl
+}
+
+fn render(c: &mut Criterion) {
+    let sdl = large_schema();
+
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+    group.bench_function("large schema", |b| {
+        b.iter(|| {
+            futures::executor::block_on(docql::main(BenchRuntime { sdl: sdl.clone() })).unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn uses(c: &mut Criterion) {
+    let schema = docql::bench::Schema::parse(&large_schema()).unwrap();
+
+    let mut group = c.benchmark_group("uses");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+    group.bench_function("find_uses per page", |b| {
+        b.iter(|| docql::bench::uses_per_page(&schema))
+    });
+    group.bench_function("UseIndex", |b| {
+        b.iter(|| docql::bench::uses_indexed(&schema))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, render, uses);
+criterion_main!(benches);
@@ -28,6 +28,23 @@ class Runtime {
         return await fs.readFile(path, { encoding: 'utf8' })
     }
 
+    async readBinaryFile(path) {
+        return await fs.readFile(path)
+    }
+
+    async readDirectory(path) {
+        const entries = await fs.readdir(path, { withFileTypes: true })
+        return entries.filter(entry => entry.isFile()).map(entry => entry.name)
+    }
+
+    async print(text) {
+        process.stdout.write(text)
+    }
+
+    async log(message) {
+        console.error(message)
+    }
+
     async prepareOutputDirectory(output) {
         await fs.mkdir(output, { recursive: true })
     }
@@ -24,6 +24,14 @@ extern "C" {
     async fn query(this: &Runtime, url: String, graphql: JsValue, headers: JsValue) -> Result<JsValue, JsValue>;
     #[wasm_bindgen(method, catch, js_name = readFile)]
     async fn read_file(this: &Runtime, path: String) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(method, catch, js_name = readBinaryFile)]
+    async fn read_binary_file(this: &Runtime, path: String) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(method, catch, js_name = readDirectory)]
+    async fn read_directory(this: &Runtime, path: String) -> Result<JsValue, JsValue>;
+    #[wasm_bindgen(method)]
+    async fn print(this: &Runtime, text: String);
+    #[wasm_bindgen(method)]
+    async fn log(this: &Runtime, message: String);
     #[wasm_bindgen(method, catch, js_name = prepareOutputDirectory)]
     async fn prepare_output_directory(this: &Runtime, output: String) -> Result<(), JsValue>;
     #[wasm_bindgen(method, catch, js_name = writeFile)]
@@ -65,6 +73,25 @@ impl RuntimeTrait for WasmRuntime {
         Ok(javascript_to_string(s))
     }
 
+    async fn read_binary_file(&self, path: &str) -> Result<Vec<u8>, Self::Error> {
+        let buffer = self.0.read_binary_file(path.to_string()).await.map_err(javascript_to_string)?;
+        Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+    }
+
+    async fn read_directory(&self, path: &str) -> Result<Vec<String>, Self::Error> {
+        let files = self.0.read_directory(path.to_string()).await.map_err(javascript_to_string)?;
+        let files = js_sys::Array::from(&files);
+        Ok(files.iter().map(javascript_to_string).collect())
+    }
+
+    async fn print(&self, text: &str) {
+        self.0.print(text.to_string()).await;
+    }
+
+    async fn log(&self, message: &str) {
+        self.0.log(message.to_string()).await;
+    }
+
     async fn prepare_output_directory(&self, output: &str) -> Result<(), Self::Error> {
         self.0
             .prepare_output_directory(output.to_string())
@@ -0,0 +1,90 @@
+//! Internals that the benchmarks measure on their own. Not part of the public API.
+
+use super::{schema, sdl, Result};
+use serde::Serialize;
+
+/// A parsed schema, so that the benchmarks don't measure parsing
+pub struct Schema(schema::Schema);
+
+impl Schema {
+    /// Parse an SDL document.
+    pub fn parse(sdl: &str) -> Result<Self> {
+        sdl::parse_schema(sdl).map(Self)
+    }
+}
+
+/// Find and serialize the uses of every type the way the type pages did before [`schema::UseIndex`]:
+/// scan the whole schema once per page, and serialize the entire using type with every use.
+///
+/// Returns the total length of the serialized uses.
+pub fn uses_per_page(schema: &Schema) -> usize {
+    let schema = &schema.0;
+    schema
+        .types
+        .iter()
+        .map(|full_type| {
+            let uses = find_uses(schema, full_type);
+            serde_json::to_string(&uses).unwrap().len()
+        })
+        .sum()
+}
+
+/// Find and serialize the uses of every type the way the type pages do now.
+///
+/// Returns the total length of the serialized uses.
+pub fn uses_indexed(schema: &Schema) -> usize {
+    let schema = &schema.0;
+    let index = schema::UseIndex::new(schema);
+    schema
+        .types
+        .iter()
+        .map(|full_type| serde_json::to_string(index.get(full_type)).unwrap().len())
+        .sum()
+}
+
+/// A use of a type that carries the entire using type, as uses were serialized before
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(tag = "use_type")]
+enum TypeUse<'a> {
+    Field {
+        #[serde(rename = "type")]
+        typ: &'a schema::FullType,
+        field: &'a schema::Field,
+    },
+    InputField {
+        #[serde(rename = "type")]
+        typ: &'a schema::FullType,
+        input_field: &'a schema::InputValue,
+    },
+    PossibleType {
+        #[serde(rename = "type")]
+        typ: &'a schema::FullType,
+    },
+}
+
+fn find_uses<'a>(schema: &'a schema::Schema, full_type: &schema::FullType) -> Vec<TypeUse<'a>> {
+    let is_use =
+        |type_ref: &schema::TypeRef| type_ref.named_type().name.as_ref() == Some(&full_type.name);
+    let mut uses = Vec::new();
+
+    for typ in &schema.types {
+        for field in typ.fields.iter().flatten() {
+            if is_use(&field.typ) || field.args.iter().any(|arg| is_use(&arg.typ)) {
+                uses.push(TypeUse::Field { typ, field });
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            if is_use(&input_field.typ) {
+                uses.push(TypeUse::InputField { typ, input_field });
+            }
+        }
+        for possible_type in typ.possible_types.iter().flatten() {
+            if is_use(possible_type) {
+                uses.push(TypeUse::PossibleType { typ });
+            }
+        }
+    }
+
+    uses.sort();
+    uses
+}
@@ -14,7 +14,7 @@ impl RuntimeTrait for Runtime {
     type Error = String;
 
     async fn date(&self) -> Result<String, Self::Error> {
-        Ok(Local::today().format("%Y-%m-%d").to_string())
+        Ok(Local::now().format("%Y-%m-%d").to_string())
     }
 
     async fn get_args(&self) -> Result<Vec<String>, Self::Error> {
@@ -44,14 +44,22 @@ impl RuntimeTrait for Runtime {
             .headers(header_map)
             .send()
             .await
-            .map_err(|e| e.to_string())?
-            .error_for_status()
-            .map_err(|e| e.to_string())?
-            .json()
-            .await
             .map_err(|e| e.to_string())?;
 
-        Ok(response)
+        let status = response.status();
+        let status_error = || format!("HTTP status {} for url ({})", status, url);
+        let body: Value = match response.json().await {
+            Ok(body) => body,
+            Err(e) if status.is_success() => return Err(e.to_string()),
+            Err(_) => return Err(status_error()),
+        };
+        // Servers explain why they rejected a request, like when introspection is disabled or
+        // the credentials are wrong, in the errors of the body, so pass those on
+        if status.is_success() || body.get("errors").is_some() {
+            Ok(body)
+        } else {
+            Err(status_error())
+        }
     }
 
     async fn read_file(&self, path: &str) -> Result<String, Self::Error> {
@@ -60,6 +68,34 @@ impl RuntimeTrait for Runtime {
             .map_err(|e| e.to_string())
     }
 
+    async fn read_binary_file(&self, path: &str) -> Result<Vec<u8>, Self::Error> {
+        tokio::fs::read(path).await.map_err(|e| e.to_string())
+    }
+
+    async fn read_directory(&self, path: &str) -> Result<Vec<String>, Self::Error> {
+        let mut entries = tokio::fs::read_dir(path).await.map_err(|e| e.to_string())?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+            if entry
+                .file_type()
+                .await
+                .map_err(|e| e.to_string())?
+                .is_file()
+            {
+                files.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        Ok(files)
+    }
+
+    async fn print(&self, text: &str) {
+        print!("{}", text);
+    }
+
+    async fn log(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+
     async fn prepare_output_directory(&self, output: &str) -> Result<(), Self::Error> {
         tokio::fs::create_dir_all(output)
             .await
@@ -0,0 +1,173 @@
+//! How much of a schema is documented.
+//!
+//! Types, fields, arguments, input fields and enum values count as documented when they have a
+//! description that isn't blank. Introspection types and built-in scalars are left out.
+
+use super::schema;
+
+/// The documentation coverage of a whole schema
+#[derive(Debug)]
+pub struct Coverage<'a> {
+    /// Every type defined by the schema, sorted by name
+    pub types: Vec<TypeCoverage<'a>>,
+}
+
+/// The documentation coverage of a type and its members
+#[derive(Debug)]
+pub struct TypeCoverage<'a> {
+    pub typ: &'a schema::FullType,
+    pub documented: usize,
+    pub total: usize,
+    /// The members without a description, and the type itself if it has none
+    pub undocumented: Vec<Item>,
+}
+
+/// Something that could have a description
+#[derive(Debug, PartialEq, Eq)]
+pub struct Item {
+    /// The name relative to the type, like `name` or `name(format:)`, or empty for the type
+    pub name: String,
+    /// The id of the element on the type's page
+    pub anchor: Option<String>,
+}
+
+impl<'a> Coverage<'a> {
+    pub fn new(schema: &'a schema::Schema) -> Self {
+        let mut types: Vec<TypeCoverage<'a>> = schema
+            .types
+            .iter()
+            .filter(|typ| !typ.is_built_in())
+            .map(TypeCoverage::new)
+            .collect();
+        types.sort_by(|a, b| a.typ.name.cmp(&b.typ.name));
+
+        Self { types }
+    }
+
+    pub fn documented(&self) -> usize {
+        self.types.iter().map(|t| t.documented).sum()
+    }
+
+    pub fn total(&self) -> usize {
+        self.types.iter().map(|t| t.total).sum()
+    }
+
+    /// The percentage of documented items, which is 100 for an empty schema
+    pub fn percent(&self) -> f64 {
+        percent(self.documented(), self.total())
+    }
+}
+
+impl<'a> TypeCoverage<'a> {
+    fn new(typ: &'a schema::FullType) -> Self {
+        let mut coverage = Self {
+            typ,
+            documented: 0,
+            total: 0,
+            undocumented: Vec::new(),
+        };
+
+        coverage.count(&typ.description, String::new(), None);
+        for field in typ.fields.iter().flatten() {
+            coverage.count(
+                &field.description,
+                field.name.clone(),
+                Some(format!("field.{}", field.name)),
+            );
+            for arg in &field.args {
+                coverage.count(
+                    &arg.description,
+                    format!("{}({}:)", field.name, arg.name),
+                    Some(format!("field.{}.arg.{}", field.name, arg.name)),
+                );
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            coverage.count(
+                &input_field.description,
+                input_field.name.clone(),
+                Some(format!("input_field.{}", input_field.name)),
+            );
+        }
+        for enum_value in typ.enum_values.iter().flatten() {
+            coverage.count(
+                &enum_value.description,
+                enum_value.name.clone(),
+                Some(format!("enum_value.{}", enum_value.name)),
+            );
+        }
+
+        coverage
+    }
+
+    fn count(&mut self, description: &Option<String>, name: String, anchor: Option<String>) {
+        self.total += 1;
+        match description {
+            Some(description) if !description.trim().is_empty() => self.documented += 1,
+            _ => self.undocumented.push(Item { name, anchor }),
+        }
+    }
+
+    pub fn percent(&self) -> f64 {
+        percent(self.documented, self.total)
+    }
+}
+
+fn percent(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_coverage() {
+        let schema = parse_schema(
+            r#"
+            "The root"
+            type Query {
+              "Look up a user"
+              user(id: ID!): User
+            }
+            type User {
+              "The name"
+              name: String
+              role: Role
+            }
+            enum Role { "Can do anything" ADMIN GUEST }
+            "#,
+        )
+        .unwrap();
+
+        let coverage = Coverage::new(&schema);
+        let types: Vec<(&str, usize, usize)> = coverage
+            .types
+            .iter()
+            .map(|t| (t.typ.name.as_str(), t.documented, t.total))
+            .collect();
+        assert_eq!(types, vec![("Query", 2, 3), ("Role", 1, 3), ("User", 1, 3)]);
+        assert_eq!(coverage.documented(), 4);
+        assert_eq!(coverage.total(), 9);
+
+        assert_eq!(
+            coverage.types[0].undocumented,
+            vec![Item {
+                name: "user(id:)".to_string(),
+                anchor: Some("field.user.arg.id".to_string()),
+            }]
+        );
+        assert_eq!(
+            coverage.types[2].undocumented[0],
+            Item {
+                name: String::new(),
+                anchor: None,
+            }
+        );
+    }
+}
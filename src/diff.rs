@@ -0,0 +1,804 @@
+//! Compare two versions of a schema and classify each change by how it affects existing clients.
+//!
+//! The classification follows `findBreakingChanges` and `findDangerousChanges` from graphql-js:
+//! breaking changes make previously valid operations invalid, dangerous changes keep operations
+//! valid but may change how they behave, and safe changes can't affect existing operations.
+
+use super::schema::{self, Kind, TypeRef};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// How a change affects existing clients
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Breaking,
+    Dangerous,
+    Safe,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Breaking => "BREAKING",
+            Self::Dangerous => "DANGEROUS",
+            Self::Safe => "SAFE",
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Breaking => "Breaking changes",
+            Self::Dangerous => "Dangerous changes",
+            Self::Safe => "Safe changes",
+        }
+    }
+}
+
+/// A single difference between the old and the new schema
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Change {
+    pub severity: Severity,
+    /// A machine readable name for the kind of change, like `field_removed`
+    pub kind: &'static str,
+    /// The schema coordinate of the changed element, like `User.name(format:)`
+    pub path: String,
+    pub message: String,
+}
+
+impl Change {
+    /// The name of the type, or `@` and the name of the directive, that the change belongs to.
+    /// Changes to the root types of the schema belong to `schema`.
+    pub fn type_name(&self) -> &str {
+        self.path.split(['.', '(']).next().unwrap_or(&self.path)
+    }
+}
+
+/// Everything that changed between two versions of a schema
+#[derive(Debug, Serialize)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    pub fn new(old: &schema::Schema, new: &schema::Schema) -> Self {
+        let mut differ = Differ {
+            changes: Vec::new(),
+        };
+        differ.schema(old, new);
+        let mut changes = differ.changes;
+        changes.sort_by(|a, b| (a.severity, &a.path).cmp(&(b.severity, &b.path)));
+        Self { changes }
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.severity == severity)
+            .count()
+    }
+
+    /// The changes as plain text, one per line.
+    pub fn to_text(&self) -> String {
+        if self.changes.is_empty() {
+            return "No changes\n".to_string();
+        }
+
+        let mut out = String::new();
+        for change in &self.changes {
+            let _ = writeln!(
+                out,
+                "{:<9}  {}: {}",
+                change.severity.label(),
+                change.path,
+                change.message
+            );
+        }
+        let _ = writeln!(
+            out,
+            "\n{} breaking, {} dangerous, {} safe",
+            self.count(Severity::Breaking),
+            self.count(Severity::Dangerous),
+            self.count(Severity::Safe)
+        );
+        out
+    }
+
+    /// The changes as a Markdown document, grouped by severity.
+    pub fn to_markdown(&self) -> String {
+        if self.changes.is_empty() {
+            return "No changes\n".to_string();
+        }
+
+        let mut out = String::new();
+        for severity in &[Severity::Breaking, Severity::Dangerous, Severity::Safe] {
+            let changes: Vec<&Change> = self
+                .changes
+                .iter()
+                .filter(|change| change.severity == *severity)
+                .collect();
+            if changes.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "## {} ({})\n", severity.heading(), changes.len());
+            for change in changes {
+                let _ = writeln!(out, "- `{}`: {}", change.path, change.message);
+            }
+        }
+        out
+    }
+}
+
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(&mut self, severity: Severity, kind: &'static str, path: String, message: String) {
+        self.changes.push(Change {
+            severity,
+            kind,
+            path,
+            message,
+        });
+    }
+
+    fn schema(&mut self, old: &schema::Schema, new: &schema::Schema) {
+        let roots = [
+            ("query", &old.query_type, &new.query_type),
+            ("mutation", &old.mutation_type, &new.mutation_type),
+            (
+                "subscription",
+                &old.subscription_type,
+                &new.subscription_type,
+            ),
+        ];
+        for (operation, old_root, new_root) in &roots {
+            let old_root = old_root.as_ref().map(|r| r.name.as_str());
+            let new_root = new_root.as_ref().map(|r| r.name.as_str());
+            match (old_root, new_root) {
+                (Some(old_root), Some(new_root)) if old_root != new_root => self.push(
+                    Severity::Breaking,
+                    "root_type_changed",
+                    format!("schema.{}", operation),
+                    format!(
+                        "Root {} type changed from `{}` to `{}`",
+                        operation, old_root, new_root
+                    ),
+                ),
+                (Some(old_root), None) => self.push(
+                    Severity::Breaking,
+                    "root_type_removed",
+                    format!("schema.{}", operation),
+                    format!("Root {} type `{}` was removed", operation, old_root),
+                ),
+                (None, Some(new_root)) => self.push(
+                    Severity::Safe,
+                    "root_type_added",
+                    format!("schema.{}", operation),
+                    format!("Root {} type `{}` was added", operation, new_root),
+                ),
+                _ => {}
+            }
+        }
+
+        let new_types: HashMap<&str, &schema::FullType> =
+            new.types.iter().map(|t| (t.name.as_str(), t)).collect();
+        for old_type in &old.types {
+            match new_types.get(old_type.name.as_str()) {
+                Some(new_type) => self.typ(old_type, new_type),
+                None => self.push(
+                    Severity::Breaking,
+                    "type_removed",
+                    old_type.name.clone(),
+                    format!(
+                        "{} `{}` was removed",
+                        kind_name(old_type.kind),
+                        old_type.name
+                    ),
+                ),
+            }
+        }
+        for new_type in &new.types {
+            if !old.types.iter().any(|t| t.name == new_type.name) {
+                self.push(
+                    Severity::Safe,
+                    "type_added",
+                    new_type.name.clone(),
+                    format!("{} `{}` was added", kind_name(new_type.kind), new_type.name),
+                );
+            }
+        }
+
+        for old_directive in &old.directives {
+            match new.directives.iter().find(|d| d.name == old_directive.name) {
+                Some(new_directive) => self.directive(old_directive, new_directive),
+                None => self.push(
+                    Severity::Breaking,
+                    "directive_removed",
+                    format!("@{}", old_directive.name),
+                    format!("Directive `@{}` was removed", old_directive.name),
+                ),
+            }
+        }
+        for new_directive in &new.directives {
+            if !old.directives.iter().any(|d| d.name == new_directive.name) {
+                self.push(
+                    Severity::Safe,
+                    "directive_added",
+                    format!("@{}", new_directive.name),
+                    format!("Directive `@{}` was added", new_directive.name),
+                );
+            }
+        }
+    }
+
+    fn typ(&mut self, old: &schema::FullType, new: &schema::FullType) {
+        let path = &old.name;
+
+        if old.kind != new.kind {
+            self.push(
+                Severity::Breaking,
+                "type_kind_changed",
+                path.clone(),
+                format!(
+                    "`{}` changed from {} to {}",
+                    path,
+                    kind_name(old.kind).to_lowercase(),
+                    kind_name(new.kind).to_lowercase()
+                ),
+            );
+            return;
+        }
+
+        if old.description != new.description {
+            self.push(
+                Severity::Safe,
+                "type_description_changed",
+                path.clone(),
+                format!("Description of `{}` changed", path),
+            );
+        }
+
+        match old.kind {
+            Kind::Object | Kind::Interface => {
+                self.fields(old, new);
+                self.names(
+                    path,
+                    old.interfaces.iter().flatten(),
+                    new.interfaces.iter().flatten(),
+                    ("interface_removed", "interface_added"),
+                    "Interface",
+                );
+            }
+            Kind::Union => self.names(
+                path,
+                old.possible_types.iter().flatten(),
+                new.possible_types.iter().flatten(),
+                ("union_member_removed", "union_member_added"),
+                "Member",
+            ),
+            Kind::InputObject => self.input_fields(old, new),
+            Kind::Enum => self.enum_values(old, new),
+            Kind::Scalar => {
+                if old.specified_by_url != new.specified_by_url {
+                    self.push(
+                        Severity::Safe,
+                        "specified_by_url_changed",
+                        path.clone(),
+                        format!("Specification URL of `{}` changed", path),
+                    );
+                }
+            }
+            Kind::List | Kind::NonNull => {}
+        }
+    }
+
+    fn fields(&mut self, old: &schema::FullType, new: &schema::FullType) {
+        let new_fields = new.fields.as_deref().unwrap_or_default();
+        for old_field in old.fields.iter().flatten() {
+            let path = format!("{}.{}", old.name, old_field.name);
+            let new_field = match new_fields.iter().find(|f| f.name == old_field.name) {
+                Some(new_field) => new_field,
+                None => {
+                    let message = if old_field.is_deprecated {
+                        format!("Deprecated field `{}` was removed", path)
+                    } else {
+                        format!("Field `{}` was removed", path)
+                    };
+                    self.push(Severity::Breaking, "field_removed", path, message);
+                    continue;
+                }
+            };
+
+            if old_field.typ != new_field.typ {
+                let severity = if is_safe_output_change(&old_field.typ, &new_field.typ) {
+                    Severity::Safe
+                } else {
+                    Severity::Breaking
+                };
+                self.push(
+                    severity,
+                    "field_type_changed",
+                    path.clone(),
+                    format!(
+                        "Field `{}` changed type from `{}` to `{}`",
+                        path, old_field.typ, new_field.typ
+                    ),
+                );
+            }
+            if old_field.description != new_field.description {
+                self.push(
+                    Severity::Safe,
+                    "field_description_changed",
+                    path.clone(),
+                    format!("Description of `{}` changed", path),
+                );
+            }
+            self.deprecation(
+                &path,
+                "field",
+                (old_field.is_deprecated, &old_field.deprecation_reason),
+                (new_field.is_deprecated, &new_field.deprecation_reason),
+            );
+            self.args(&path, &old_field.args, &new_field.args);
+        }
+
+        for new_field in new_fields {
+            if !old
+                .fields
+                .iter()
+                .flatten()
+                .any(|f| f.name == new_field.name)
+            {
+                let path = format!("{}.{}", new.name, new_field.name);
+                self.push(
+                    Severity::Safe,
+                    "field_added",
+                    path.clone(),
+                    format!("Field `{}` was added", path),
+                );
+            }
+        }
+    }
+
+    fn args(&mut self, parent: &str, old: &[schema::InputValue], new: &[schema::InputValue]) {
+        for old_arg in old {
+            let path = format!("{}({}:)", parent, old_arg.name);
+            match new.iter().find(|a| a.name == old_arg.name) {
+                Some(new_arg) => self.input_value(&path, "argument", old_arg, new_arg),
+                None => self.push(
+                    Severity::Breaking,
+                    "argument_removed",
+                    path.clone(),
+                    format!("Argument `{}` was removed", path),
+                ),
+            }
+        }
+        for new_arg in new {
+            if !old.iter().any(|a| a.name == new_arg.name) {
+                let path = format!("{}({}:)", parent, new_arg.name);
+                if is_required(new_arg) {
+                    self.push(
+                        Severity::Breaking,
+                        "required_argument_added",
+                        path.clone(),
+                        format!("Required argument `{}` was added", path),
+                    );
+                } else {
+                    self.push(
+                        Severity::Dangerous,
+                        "optional_argument_added",
+                        path.clone(),
+                        format!("Optional argument `{}` was added", path),
+                    );
+                }
+            }
+        }
+    }
+
+    fn input_fields(&mut self, old: &schema::FullType, new: &schema::FullType) {
+        let new_fields = new.input_fields.as_deref().unwrap_or_default();
+        for old_field in old.input_fields.iter().flatten() {
+            let path = format!("{}.{}", old.name, old_field.name);
+            match new_fields.iter().find(|f| f.name == old_field.name) {
+                Some(new_field) => self.input_value(&path, "input field", old_field, new_field),
+                None => self.push(
+                    Severity::Breaking,
+                    "input_field_removed",
+                    path.clone(),
+                    format!("Input field `{}` was removed", path),
+                ),
+            }
+        }
+        for new_field in new_fields {
+            if !old
+                .input_fields
+                .iter()
+                .flatten()
+                .any(|f| f.name == new_field.name)
+            {
+                let path = format!("{}.{}", new.name, new_field.name);
+                if is_required(new_field) {
+                    self.push(
+                        Severity::Breaking,
+                        "required_input_field_added",
+                        path.clone(),
+                        format!("Required input field `{}` was added", path),
+                    );
+                } else {
+                    self.push(
+                        Severity::Dangerous,
+                        "optional_input_field_added",
+                        path.clone(),
+                        format!("Optional input field `{}` was added", path),
+                    );
+                }
+            }
+        }
+        if old.is_one_of != new.is_one_of {
+            let (severity, message) = if new.is_one_of == Some(true) {
+                (Severity::Breaking, "became a @oneOf input object")
+            } else {
+                (Severity::Safe, "is no longer a @oneOf input object")
+            };
+            self.push(
+                severity,
+                "one_of_changed",
+                old.name.clone(),
+                format!("`{}` {}", old.name, message),
+            );
+        }
+    }
+
+    /// Compare an argument or input field that exists in both schemas.
+    fn input_value(
+        &mut self,
+        path: &str,
+        what: &str,
+        old: &schema::InputValue,
+        new: &schema::InputValue,
+    ) {
+        let kind = |suffix: &str| -> &'static str {
+            match (what, suffix) {
+                ("argument", "type") => "argument_type_changed",
+                ("argument", "default") => "argument_default_changed",
+                ("argument", "description") => "argument_description_changed",
+                ("input field", "type") => "input_field_type_changed",
+                ("input field", "default") => "input_field_default_changed",
+                _ => "input_field_description_changed",
+            }
+        };
+
+        if old.typ != new.typ {
+            let severity = if is_safe_input_change(&old.typ, &new.typ) {
+                Severity::Safe
+            } else {
+                Severity::Breaking
+            };
+            self.push(
+                severity,
+                kind("type"),
+                path.to_string(),
+                format!(
+                    "Type of {} `{}` changed from `{}` to `{}`",
+                    what, path, old.typ, new.typ
+                ),
+            );
+        }
+        if old.default_value != new.default_value {
+            self.push(
+                Severity::Dangerous,
+                kind("default"),
+                path.to_string(),
+                format!(
+                    "Default value of {} `{}` changed from `{}` to `{}`",
+                    what,
+                    path,
+                    old.default_value.as_deref().unwrap_or("none"),
+                    new.default_value.as_deref().unwrap_or("none")
+                ),
+            );
+        }
+        if old.description != new.description {
+            self.push(
+                Severity::Safe,
+                kind("description"),
+                path.to_string(),
+                format!("Description of `{}` changed", path),
+            );
+        }
+        self.deprecation(
+            path,
+            what,
+            (old.is_deprecated, &old.deprecation_reason),
+            (new.is_deprecated, &new.deprecation_reason),
+        );
+    }
+
+    fn enum_values(&mut self, old: &schema::FullType, new: &schema::FullType) {
+        let new_values = new.enum_values.as_deref().unwrap_or_default();
+        for old_value in old.enum_values.iter().flatten() {
+            let path = format!("{}.{}", old.name, old_value.name);
+            match new_values.iter().find(|v| v.name == old_value.name) {
+                Some(new_value) => {
+                    if old_value.description != new_value.description {
+                        self.push(
+                            Severity::Safe,
+                            "enum_value_description_changed",
+                            path.clone(),
+                            format!("Description of `{}` changed", path),
+                        );
+                    }
+                    self.deprecation(
+                        &path,
+                        "enum value",
+                        (old_value.is_deprecated, &old_value.deprecation_reason),
+                        (new_value.is_deprecated, &new_value.deprecation_reason),
+                    );
+                }
+                None => self.push(
+                    Severity::Breaking,
+                    "enum_value_removed",
+                    path.clone(),
+                    format!("Enum value `{}` was removed", path),
+                ),
+            }
+        }
+        for new_value in new_values {
+            if !old
+                .enum_values
+                .iter()
+                .flatten()
+                .any(|v| v.name == new_value.name)
+            {
+                let path = format!("{}.{}", new.name, new_value.name);
+                self.push(
+                    Severity::Dangerous,
+                    "enum_value_added",
+                    path.clone(),
+                    format!("Enum value `{}` was added", path),
+                );
+            }
+        }
+    }
+
+    /// Compare lists of named types, like the interfaces of an object or the members of a union.
+    fn names<'a>(
+        &mut self,
+        parent: &str,
+        old: impl Iterator<Item = &'a TypeRef>,
+        new: impl Iterator<Item = &'a TypeRef>,
+        (removed, added): (&'static str, &'static str),
+        what: &str,
+    ) {
+        let old: Vec<&str> = old.filter_map(|t| t.name.as_deref()).collect();
+        let new: Vec<&str> = new.filter_map(|t| t.name.as_deref()).collect();
+        for name in &old {
+            if !new.contains(name) {
+                self.push(
+                    Severity::Breaking,
+                    removed,
+                    parent.to_string(),
+                    format!("{} `{}` was removed from `{}`", what, name, parent),
+                );
+            }
+        }
+        for name in &new {
+            if !old.contains(name) {
+                self.push(
+                    Severity::Dangerous,
+                    added,
+                    parent.to_string(),
+                    format!("{} `{}` was added to `{}`", what, name, parent),
+                );
+            }
+        }
+    }
+
+    fn deprecation(
+        &mut self,
+        path: &str,
+        what: &str,
+        (old_deprecated, old_reason): (bool, &Option<String>),
+        (new_deprecated, new_reason): (bool, &Option<String>),
+    ) {
+        match (old_deprecated, new_deprecated) {
+            (false, true) => self.push(
+                Severity::Safe,
+                "deprecation_added",
+                path.to_string(),
+                format!(
+                    "{} `{}` was deprecated{}",
+                    capitalize(what),
+                    path,
+                    new_reason
+                        .as_deref()
+                        .map(|reason| format!(": {}", reason))
+                        .unwrap_or_default()
+                ),
+            ),
+            (true, false) => self.push(
+                Severity::Safe,
+                "deprecation_removed",
+                path.to_string(),
+                format!("{} `{}` is no longer deprecated", capitalize(what), path),
+            ),
+            (true, true) if old_reason != new_reason => self.push(
+                Severity::Safe,
+                "deprecation_reason_changed",
+                path.to_string(),
+                format!("Deprecation reason of `{}` changed", path),
+            ),
+            _ => {}
+        }
+    }
+
+    fn directive(&mut self, old: &schema::Directive, new: &schema::Directive) {
+        let path = format!("@{}", old.name);
+
+        for location in &old.locations {
+            if !new.locations.contains(location) {
+                self.push(
+                    Severity::Breaking,
+                    "directive_location_removed",
+                    path.clone(),
+                    format!("Location {} was removed from `{}`", location, path),
+                );
+            }
+        }
+        for location in &new.locations {
+            if !old.locations.contains(location) {
+                self.push(
+                    Severity::Safe,
+                    "directive_location_added",
+                    path.clone(),
+                    format!("Location {} was added to `{}`", location, path),
+                );
+            }
+        }
+        if old.is_repeatable == Some(true) && new.is_repeatable == Some(false) {
+            self.push(
+                Severity::Breaking,
+                "directive_repeatable_removed",
+                path.clone(),
+                format!("`{}` is no longer repeatable", path),
+            );
+        }
+        self.args(&path, &old.args, &new.args);
+    }
+}
+
+/// Whether clients that read the old output type can still read the new one.
+fn is_safe_output_change(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old.kind, new.kind) {
+        (Kind::List, Kind::List) | (Kind::NonNull, Kind::NonNull) => {
+            is_safe_wrapped(old, new, is_safe_output_change)
+        }
+        (Kind::NonNull, _) => false,
+        // Returning a non-null value where a nullable one was expected is always fine
+        (_, Kind::NonNull) => new
+            .of_type
+            .as_deref()
+            .is_some_and(|new| is_safe_output_change(old, new)),
+        (Kind::List, _) | (_, Kind::List) => false,
+        _ => old.name == new.name,
+    }
+}
+
+/// Whether values that clients sent for the old input type are still accepted by the new one.
+fn is_safe_input_change(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old.kind, new.kind) {
+        (Kind::List, Kind::List) | (Kind::NonNull, Kind::NonNull) => {
+            is_safe_wrapped(old, new, is_safe_input_change)
+        }
+        // Accepting null where a non-null value was required is always fine
+        (Kind::NonNull, _) => old
+            .of_type
+            .as_deref()
+            .is_some_and(|old| is_safe_input_change(old, new)),
+        (_, Kind::NonNull) | (Kind::List, _) | (_, Kind::List) => false,
+        _ => old.name == new.name,
+    }
+}
+
+/// Compare the types inside two list or two non-null wrappers.
+fn is_safe_wrapped(old: &TypeRef, new: &TypeRef, is_safe: fn(&TypeRef, &TypeRef) -> bool) -> bool {
+    match (old.of_type.as_deref(), new.of_type.as_deref()) {
+        (Some(old), Some(new)) => is_safe(old, new),
+        _ => false,
+    }
+}
+
+fn is_required(input_value: &schema::InputValue) -> bool {
+    input_value.typ.kind == Kind::NonNull && input_value.default_value.is_none()
+}
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Object => "Object",
+        Kind::InputObject => "Input object",
+        Kind::Interface => "Interface",
+        Kind::Union => "Union",
+        Kind::Enum => "Enum",
+        Kind::Scalar => "Scalar",
+        Kind::List => "List",
+        Kind::NonNull => "Non-null",
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_classify_changes() {
+        let old = parse_schema(
+            r#"
+            type Query {
+              user(id: ID!): User
+              users: [User!]
+              legacy: String @deprecated
+            }
+            type User { name: String, role: Role, age: Int }
+            enum Role { ADMIN GUEST }
+            input Filter { name: String! }
+            "#,
+        )
+        .unwrap();
+        let new = parse_schema(
+            r#"
+            type Query {
+              user(id: ID!, tenant: ID!): User
+              users(first: Int): [User!]!
+            }
+            type User { name: Int, role: Role, age: Int, email: String }
+            enum Role { ADMIN MEMBER }
+            input Filter { name: String }
+            "#,
+        )
+        .unwrap();
+
+        let diff = Diff::new(&old, &new);
+        let changes: Vec<(Severity, &str, &str)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.severity, c.kind, c.path.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (Severity::Breaking, "field_removed", "Query.legacy"),
+                (
+                    Severity::Breaking,
+                    "required_argument_added",
+                    "Query.user(tenant:)"
+                ),
+                (Severity::Breaking, "enum_value_removed", "Role.GUEST"),
+                (Severity::Breaking, "field_type_changed", "User.name"),
+                (
+                    Severity::Dangerous,
+                    "optional_argument_added",
+                    "Query.users(first:)"
+                ),
+                (Severity::Dangerous, "enum_value_added", "Role.MEMBER"),
+                (Severity::Safe, "input_field_type_changed", "Filter.name"),
+                (Severity::Safe, "field_type_changed", "Query.users"),
+                (Severity::Safe, "field_added", "User.email"),
+            ]
+        );
+    }
+}
@@ -13,10 +13,18 @@ pub enum Error {
     #[error("Failed to execute introspection query: {0}")]
     Query(String),
 
+    /// The GraphQL introspection response contained errors and no data
+    #[error("The GraphQL introspection response contained errors:{}", .0.iter().map(|e| format!("\n  - {}", e)).collect::<String>())]
+    GraphQL(Vec<String>),
+
     /// The call to get the GraphQL Schema from the runtime via a schema file failed
     #[error("Failed to read schema file: {0}")]
     ReadSchemaFile(String),
 
+    /// The call to the runtime to list the files in a directory failed
+    #[error("Failed to read directory '{0}': {1}")]
+    ReadDirectory(String, String),
+
     /// The call to the runtime to prepare the output directory failed
     #[error("Failed to prepare output directory '{0}': {1}")]
     PrepareOutputDirectory(String, String),
@@ -29,13 +37,45 @@ pub enum Error {
     #[error("Failed to parse GraphQL Introspection response: {0}")]
     Serde(#[from] serde_json::Error),
 
+    /// Parsing a GraphQL schema definition language (SDL) file failed
+    #[error("Failed to parse GraphQL SDL at line {0}, column {1}: {2}")]
+    SdlParse(usize, usize, String),
+
+    /// The schema file did not match any of the known shapes of an introspection result
+    #[error("Failed to find an introspection result in the schema file. Expected one of:\n  - {{\"data\": {{\"__schema\": ...}}}}\n  - {{\"__schema\": ...}}\n  - {{\"queryType\": ..., \"types\": [...]}}\n  - any of the above nested inside other objects")]
+    UnrecognizedSchema,
+
+    /// A type reference in the schema is incomplete or malformed
+    #[error("Invalid type reference at '{0}': {1}")]
+    TypeRef(String, String),
+
+    /// Comparing two schemas found changes that break existing clients
+    #[error("Found {0} breaking change(s)")]
+    BreakingChanges(usize),
+
+    /// The schema is inconsistent in a way that would break the documentation
+    #[error("The schema is invalid:{}", .0.iter().map(|e| format!("\n  - {}", e)).collect::<String>())]
+    InvalidSchema(Vec<String>),
+
+    /// A type was asked for by name that the schema doesn't have
+    #[error("The schema has no type named '{0}'")]
+    UnknownType(String),
+
+    /// Linting a schema found violations of rules whose level is error
+    #[error("Found {0} lint error(s)")]
+    LintFailed(usize),
+
+    /// Less of the schema is documented than the minimum asked for
+    #[error("Documentation coverage is {0:.1}%, below the minimum of {1}%")]
+    InsufficientCoverage(f64, f64),
+
     /// Loading a handlebars template failed
     #[error("Failed to load handlebars template: {0}")]
-    HandlebarsTemplate(#[from] handlebars::TemplateError),
+    HandlebarsTemplate(Box<handlebars::TemplateError>),
 
     /// Rendering a handlebars template failed
     #[error("Failed to render handlebars template: {0}")]
-    HandlebarsRender(#[from] handlebars::RenderError),
+    HandlebarsRender(Box<handlebars::RenderError>),
 
     /// An error occurred parsing arguments
     #[error("Failed to parse arguments: {0}")]
@@ -51,13 +91,36 @@ impl Error {
             Self::Args(_) => 11,
             Self::Query(_) => 12,
             Self::ReadSchemaFile(_) => 13,
+            Self::GraphQL(_) => 14,
+            Self::ReadDirectory(_, _) => 15,
             Self::PrepareOutputDirectory(_, _) => 20,
             Self::WriteFile(_, _) => 21,
             Self::Serde(_) => 30,
             Self::HandlebarsTemplate(_) | Self::HandlebarsRender(_) => 31,
+            Self::SdlParse(_, _, _) => 32,
+            Self::UnrecognizedSchema => 33,
+            Self::TypeRef(_, _) => 34,
+            Self::InvalidSchema(_) => 35,
+            Self::UnknownType(_) => 36,
+            Self::BreakingChanges(_) => 40,
+            Self::LintFailed(_) => 41,
+            Self::InsufficientCoverage(_, _) => 42,
         }
     }
 }
 
+// The handlebars errors are boxed so that every `Result` doesn't have to make room for them
+impl From<handlebars::TemplateError> for Error {
+    fn from(error: handlebars::TemplateError) -> Self {
+        Self::HandlebarsTemplate(Box::new(error))
+    }
+}
+
+impl From<handlebars::RenderError> for Error {
+    fn from(error: handlebars::RenderError) -> Self {
+        Self::HandlebarsRender(Box::new(error))
+    }
+}
+
 /// Alias for a `Result` with the error type `docql::Error`.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,174 @@
+//! Leaving types and fields out of the documentation.
+//!
+//! Types are matched by name and fields (and input fields) by their `Type.field` path. Patterns
+//! are globs like `Internal*`, or regular expressions between slashes like `/^(Admin|Internal)/`.
+//! Anything excluded is removed from the schema before the documentation is generated, so it
+//! doesn't get a page and doesn't show up in the search, the uses of other types or anywhere else.
+
+use super::schema;
+
+/// A glob or a regular expression
+#[derive(Debug)]
+pub enum Pattern {
+    Glob(globset::GlobMatcher),
+    Regex(regex::Regex),
+}
+
+impl Pattern {
+    /// Parse a pattern, which is a regular expression if it is between slashes and a glob
+    /// otherwise.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+            Some(regex) => regex::Regex::new(regex)
+                .map(Self::Regex)
+                .map_err(|e| e.to_string()),
+            None => globset::Glob::new(s)
+                .map(|glob| Self::Glob(glob.compile_matcher()))
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn is_match(&self, s: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob.is_match(s),
+            Self::Regex(regex) => regex.is_match(s),
+        }
+    }
+}
+
+/// Which types and fields to document
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// If any are given, only types matching one of these are documented
+    pub include_types: Vec<Pattern>,
+    pub exclude_types: Vec<Pattern>,
+    /// If any are given, only fields whose path matches one of these are documented
+    pub include_fields: Vec<Pattern>,
+    pub exclude_fields: Vec<Pattern>,
+}
+
+impl Filter {
+    pub fn includes_type(&self, name: &str) -> bool {
+        includes(&self.include_types, &self.exclude_types, name)
+    }
+
+    pub fn includes_field(&self, type_name: &str, field_name: &str) -> bool {
+        let path = format!("{}.{}", type_name, field_name);
+        includes(&self.include_fields, &self.exclude_fields, &path)
+    }
+
+    /// Remove everything that isn't included from the schema.
+    ///
+    /// References to removed types are left in place, so the fields, arguments, interfaces and
+    /// possible types that refer to them still show their names.
+    pub fn apply(&self, schema: &mut schema::Schema) {
+        schema.types.retain(|typ| self.includes_type(&typ.name));
+        for typ in &mut schema.types {
+            let name = &typ.name;
+            if let Some(fields) = &mut typ.fields {
+                fields.retain(|field| self.includes_field(name, &field.name));
+            }
+            if let Some(input_fields) = &mut typ.input_fields {
+                input_fields.retain(|input_field| self.includes_field(name, &input_field.name));
+            }
+        }
+
+        for root in [
+            &mut schema.query_type,
+            &mut schema.mutation_type,
+            &mut schema.subscription_type,
+        ] {
+            if root
+                .as_ref()
+                .is_some_and(|root| !self.includes_type(&root.name))
+            {
+                *root = None;
+            }
+        }
+    }
+}
+
+fn includes(include: &[Pattern], exclude: &[Pattern], s: &str) -> bool {
+    (include.is_empty() || include.iter().any(|pattern| pattern.is_match(s)))
+        && !exclude.iter().any(|pattern| pattern.is_match(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlebars_helpers::TypeRefRenderer;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_filter() {
+        let mut schema = parse_schema(
+            r#"
+            type Query {
+              me: User
+              internalStats: InternalStats
+              users(where: InternalFilter!, first: Int): [User]
+            }
+            type Mutation { banUser(id: ID!): Boolean }
+            interface Node { id: ID! }
+            interface InternalNode { id: ID! }
+            type User implements Node & InternalNode { id: ID! name: String passwordHash: String }
+            type InternalStats { users: Int }
+            input InternalFilter { name: String }
+            type AdminAudit { at: String }
+            union Actor = User | AdminAudit
+            "#,
+        )
+        .unwrap();
+        let patterns = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|pattern| Pattern::parse(pattern).unwrap())
+                .collect()
+        };
+        let filter = Filter {
+            exclude_types: patterns(&["Internal*", "/^(Admin|Mutation$)/"]),
+            exclude_fields: patterns(&["*.passwordHash"]),
+            ..Filter::default()
+        };
+        filter.apply(&mut schema);
+
+        let types: Vec<&str> = schema
+            .types
+            .iter()
+            .filter(|typ| !typ.is_built_in())
+            .map(|typ| typ.name.as_str())
+            .collect();
+        assert_eq!(types, vec!["Query", "Node", "User", "Actor"]);
+        assert!(schema.mutation_type.is_none());
+
+        let fields = |name: &str| -> Vec<&schema::Field> {
+            let typ = schema.types.iter().find(|typ| typ.name == name).unwrap();
+            typ.fields.iter().flatten().collect()
+        };
+        let field_names =
+            |name: &str| -> Vec<&str> { fields(name).iter().map(|f| f.name.as_str()).collect() };
+        assert_eq!(field_names("User"), vec!["id", "name"]);
+
+        // Fields and arguments that refer to an excluded type are kept, and show its name without
+        // a link
+        assert_eq!(field_names("Query"), vec!["me", "internalStats", "users"]);
+        let users = fields("Query")[2];
+        let args: Vec<&str> = users.args.iter().map(|arg| arg.name.as_str()).collect();
+        assert_eq!(args, vec!["where", "first"]);
+
+        let mut handlebars = handlebars::Handlebars::new();
+        handlebars.register_helper("t", Box::new(TypeRefRenderer::new(&schema)));
+        let render = |type_ref: &schema::TypeRef| {
+            handlebars
+                .render_template("{{ t(this) }}", type_ref)
+                .unwrap()
+        };
+        assert_eq!(render(&users.args[0].typ), "InternalFilter!");
+        assert_eq!(
+            render(&users.typ),
+            r#"[<a class="object" href="object.User.html">User</a>]"#
+        );
+
+        assert!(Pattern::parse("/(/").is_err());
+    }
+}
@@ -0,0 +1,534 @@
+//! Diagrams of how the types in a schema refer to each other.
+//!
+//! Types are connected by the fields that return them, the interfaces they implement and the
+//! unions they are members of. A graph can cover the whole schema or only the neighborhood of one
+//! type, and is written as Graphviz DOT, as Mermaid, or as an SVG laid out here so that no `dot`
+//! binary is needed.
+
+use super::schema::{self, Kind};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+
+/// The most types a neighborhood shows, so that the diagrams of heavily used types stay readable
+const MAX_NEIGHBORHOOD: usize = 40;
+
+/// The SVG's font size, and the width of one character of it
+const FONT_SIZE: f64 = 12.0;
+const CHAR_WIDTH: f64 = 7.2;
+const NODE_HEIGHT: f64 = 24.0;
+const NODE_PADDING: f64 = 8.0;
+/// The space between the rings of the SVG, and between the types on a ring
+const RING_GAP: f64 = 90.0;
+const NODE_GAP: f64 = 16.0;
+const MARGIN: f64 = 10.0;
+
+/// Why one type refers to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeKind {
+    /// One or more fields or input fields return the type
+    Field,
+    /// The type implements the interface
+    Implements,
+    /// The union has the type as a member
+    Member,
+}
+
+#[derive(Debug)]
+pub struct Edge<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    pub kind: EdgeKind,
+    /// The fields that make up a `Field` edge
+    pub fields: Vec<&'a str>,
+}
+
+/// Every reference between the types of a schema, leaving out introspection types and built-in
+/// scalars
+pub struct Relationships<'a> {
+    types: Vec<&'a schema::FullType>,
+    by_name: HashMap<&'a str, &'a schema::FullType>,
+    edges: Vec<Edge<'a>>,
+    /// The edges going in or out of each type
+    adjacent: HashMap<&'a str, Vec<usize>>,
+    query_type: Option<&'a str>,
+}
+
+impl<'a> Relationships<'a> {
+    pub fn new(schema: &'a schema::Schema) -> Self {
+        let types: Vec<&schema::FullType> = schema
+            .types
+            .iter()
+            .filter(|typ| !typ.is_built_in())
+            .collect();
+        let by_name: HashMap<&str, &schema::FullType> =
+            types.iter().map(|typ| (typ.name.as_str(), *typ)).collect();
+
+        let mut edges: BTreeMap<(&str, &str, EdgeKind), Vec<&str>> = BTreeMap::new();
+        for typ in &types {
+            let from = typ.name.as_str();
+            let mut add =
+                |type_ref: &'a schema::TypeRef, kind: EdgeKind, field: Option<&'a str>| {
+                    let to = match type_ref.named_type().name.as_deref() {
+                        Some(to) if by_name.contains_key(to) => to,
+                        _ => return,
+                    };
+                    let fields = edges.entry((from, to, kind)).or_default();
+                    fields.extend(field);
+                };
+            for field in typ.fields.iter().flatten() {
+                add(&field.typ, EdgeKind::Field, Some(&field.name));
+            }
+            for input_field in typ.input_fields.iter().flatten() {
+                add(&input_field.typ, EdgeKind::Field, Some(&input_field.name));
+            }
+            for interface in typ.interfaces.iter().flatten() {
+                add(interface, EdgeKind::Implements, None);
+            }
+            if typ.kind == Kind::Union {
+                for member in typ.possible_types.iter().flatten() {
+                    add(member, EdgeKind::Member, None);
+                }
+            }
+        }
+
+        let edges: Vec<Edge> = edges
+            .into_iter()
+            .map(|((from, to, kind), fields)| Edge {
+                from,
+                to,
+                kind,
+                fields,
+            })
+            .collect();
+        let mut adjacent: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, edge) in edges.iter().enumerate() {
+            adjacent.entry(edge.from).or_default().push(i);
+            if edge.to != edge.from {
+                adjacent.entry(edge.to).or_default().push(i);
+            }
+        }
+
+        Self {
+            types,
+            by_name,
+            edges,
+            adjacent,
+            query_type: schema.query_type.as_ref().map(|root| root.name.as_str()),
+        }
+    }
+
+    /// Every type in the schema, arranged around the query root type
+    pub fn whole(&self) -> Graph<'_> {
+        let center = self
+            .query_type
+            .or_else(|| self.types.first().map(|typ| typ.name.as_str()));
+        let mut distances = match center {
+            Some(center) => self.distances(center, usize::MAX, usize::MAX),
+            None => Vec::new(),
+        };
+        let outside = distances.iter().map(|(_, d)| d + 1).max().unwrap_or(0);
+        let reached: HashSet<&str> = distances.iter().map(|(name, _)| *name).collect();
+        for typ in &self.types {
+            if !reached.contains(typ.name.as_str()) {
+                distances.push((&typ.name, outside));
+            }
+        }
+        self.graph(distances)
+    }
+
+    /// The types within `hops` references of the named type, in either direction
+    pub fn neighborhood(&self, name: &str, hops: usize) -> Graph<'_> {
+        let name = match self.by_name.get(name) {
+            Some(typ) => typ.name.as_str(),
+            None => return self.graph(Vec::new()),
+        };
+        self.graph(self.distances(name, hops, MAX_NEIGHBORHOOD))
+    }
+
+    /// A breadth-first search from `start` following edges in either direction, stopping at
+    /// `hops` references away or `limit` types
+    fn distances(&self, start: &'a str, hops: usize, limit: usize) -> Vec<(&'a str, usize)> {
+        let mut seen = HashSet::new();
+        let mut order = vec![(start, 0)];
+        let mut queue = VecDeque::new();
+        seen.insert(start);
+        queue.push_back((start, 0));
+
+        while let Some((name, distance)) = queue.pop_front() {
+            if distance >= hops {
+                continue;
+            }
+            for &i in self.adjacent.get(name).into_iter().flatten() {
+                let edge = &self.edges[i];
+                let next = if edge.from == name {
+                    edge.to
+                } else {
+                    edge.from
+                };
+                if seen.contains(next) {
+                    continue;
+                }
+                if order.len() >= limit {
+                    return order;
+                }
+                seen.insert(next);
+                order.push((next, distance + 1));
+                queue.push_back((next, distance + 1));
+            }
+        }
+        order
+    }
+
+    fn graph(&self, distances: Vec<(&'a str, usize)>) -> Graph<'_> {
+        let included: HashSet<&str> = distances.iter().map(|(name, _)| *name).collect();
+        // Every edge between two included types is in the adjacency list of both of them
+        let edges: BTreeSet<usize> = distances
+            .iter()
+            .flat_map(|(name, _)| self.adjacent.get(name).into_iter().flatten().copied())
+            .collect();
+        Graph {
+            nodes: distances
+                .into_iter()
+                .map(|(name, distance)| Node {
+                    typ: self.by_name[name],
+                    distance,
+                })
+                .collect(),
+            edges: edges
+                .into_iter()
+                .map(|i| &self.edges[i])
+                .filter(|edge| included.contains(edge.from) && included.contains(edge.to))
+                .collect(),
+        }
+    }
+}
+
+struct Node<'a> {
+    typ: &'a schema::FullType,
+    /// How many references away from the type the graph is arranged around
+    distance: usize,
+}
+
+/// A set of types and the references between them
+pub struct Graph<'a> {
+    /// In the order they were reached, starting with the type the graph is arranged around
+    nodes: Vec<Node<'a>>,
+    edges: Vec<&'a Edge<'a>>,
+}
+
+impl Graph<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// The graph in the Graphviz DOT language
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        out.push_str("digraph schema {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=box, fontname=\"sans-serif\"];\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "    \"{}\" [color=\"{}\", URL=\"{}\"];",
+                node.typ.name,
+                color(node.typ.kind),
+                href(node.typ),
+            );
+        }
+        for edge in &self.edges {
+            let attributes = match edge.kind {
+                EdgeKind::Field => format!("label=\"{}\"", edge.fields.join(", ")),
+                EdgeKind::Implements => "style=dashed, arrowhead=empty".to_string(),
+                EdgeKind::Member => "style=dotted".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [{}];",
+                edge.from, edge.to, attributes
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// The graph as a Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        out.push_str("flowchart LR\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "    {}[\"{}\"]:::{}",
+                mermaid_id(&node.typ.name),
+                node.typ.name,
+                node.typ.kind.prefix()
+            );
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::Field => format!("-->|{}|", edge.fields.join(", ")),
+                EdgeKind::Implements => "-.->|implements|".to_string(),
+                EdgeKind::Member => "-.->|member|".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "    {} {} {}",
+                mermaid_id(edge.from),
+                arrow,
+                mermaid_id(edge.to)
+            );
+        }
+        for kind in &[
+            Kind::Object,
+            Kind::InputObject,
+            Kind::Interface,
+            Kind::Union,
+            Kind::Enum,
+            Kind::Scalar,
+        ] {
+            let _ = writeln!(
+                out,
+                "    classDef {} stroke:{}",
+                kind.prefix(),
+                color(*kind)
+            );
+        }
+        out
+    }
+
+    /// The graph as an SVG image, with the first type in the middle and every other type on a
+    /// ring around it according to how far away it is. Each type links to its page.
+    pub fn to_svg(&self) -> String {
+        let positions = self.layout();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (0.0f64, 0.0f64, 0.0f64, 0.0f64);
+        for (node, (x, y)) in self.nodes.iter().zip(&positions) {
+            let half_width = node_width(node.typ) / 2.0;
+            min_x = min_x.min(x - half_width);
+            max_x = max_x.max(x + half_width);
+            min_y = min_y.min(y - NODE_HEIGHT / 2.0);
+            max_y = max_y.max(y + NODE_HEIGHT / 2.0);
+        }
+        let (min_x, min_y) = (min_x - MARGIN, min_y - MARGIN);
+        let (width, height) = (max_x + MARGIN - min_x, max_y + MARGIN - min_y);
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg class="graph" xmlns="http://www.w3.org/2000/svg" viewBox="{:.0} {:.0} {:.0} {:.0}" width="{:.0}" font-size="{}" font-family="monospace">"#,
+            min_x, min_y, width, height, width, FONT_SIZE
+        );
+        out.push_str(r##"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#999"/></marker></defs>"##);
+        out.push('\n');
+
+        let index: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.typ.name.as_str(), i))
+            .collect();
+        for edge in &self.edges {
+            let (from, to) = (index[edge.from], index[edge.to]);
+            if from == to {
+                continue;
+            }
+            let (x1, y1) = clip(
+                positions[from],
+                positions[to],
+                node_width(self.nodes[from].typ),
+            );
+            let (x2, y2) = clip(
+                positions[to],
+                positions[from],
+                node_width(self.nodes[to].typ),
+            );
+            let (dash, title) = match edge.kind {
+                EdgeKind::Field => ("", format!("{}: {}", edge.from, edge.fields.join(", "))),
+                EdgeKind::Implements => (
+                    r#" stroke-dasharray="6 3""#,
+                    format!("{} implements {}", edge.from, edge.to),
+                ),
+                EdgeKind::Member => (
+                    r#" stroke-dasharray="2 3""#,
+                    format!("{} is a member of {}", edge.to, edge.from),
+                ),
+            };
+            let _ = writeln!(
+                out,
+                r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#999"{} marker-end="url(#arrow)"><title>{}</title></line>"##,
+                x1, y1, x2, y2, dash, title
+            );
+        }
+
+        for (i, (node, (x, y))) in self.nodes.iter().zip(&positions).enumerate() {
+            let width = node_width(node.typ);
+            let _ = writeln!(
+                out,
+                r##"<a href="{}"><rect x="{:.1}" y="{:.1}" width="{:.1}" height="{}" rx="3" fill="#2a2a2a" stroke="{}" stroke-width="{}"/><text x="{:.1}" y="{:.1}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text></a>"##,
+                href(node.typ),
+                x - width / 2.0,
+                y - NODE_HEIGHT / 2.0,
+                width,
+                NODE_HEIGHT,
+                color(node.typ.kind),
+                if i == 0 { 2 } else { 1 },
+                x,
+                y,
+                color(node.typ.kind),
+                node.typ.name
+            );
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// The center of every node: each ring is far enough out to fit its types side by side
+    fn layout(&self) -> Vec<(f64, f64)> {
+        let mut rings: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            rings.entry(node.distance).or_default().push(i);
+        }
+
+        let mut positions = vec![(0.0, 0.0); self.nodes.len()];
+        let mut radius = 0.0;
+        let mut previous_widest = 0.0;
+        for (ring, members) in rings.values().enumerate() {
+            let widths = members.iter().map(|&i| node_width(self.nodes[i].typ));
+            let widest = widths.clone().fold(0.0, f64::max);
+            if ring == 0 && members.len() == 1 {
+                previous_widest = widest;
+                continue;
+            }
+            let circumference: f64 = widths.map(|width| width + NODE_GAP).sum();
+            // Far enough out that types beside the previous ring don't touch it either
+            radius = [
+                radius + RING_GAP,
+                radius + (previous_widest + widest) / 2.0 + RING_GAP / 2.0,
+                circumference / (2.0 * PI),
+            ]
+            .iter()
+            .copied()
+            .fold(0.0, f64::max);
+            previous_widest = widest;
+            let offset = ring as f64 * 0.5;
+            for (j, &i) in members.iter().enumerate() {
+                let angle = 2.0 * PI * (j as f64 + offset) / members.len() as f64 - PI / 2.0;
+                positions[i] = (radius * angle.cos(), radius * angle.sin());
+            }
+        }
+        positions
+    }
+}
+
+fn node_width(typ: &schema::FullType) -> f64 {
+    typ.name.len() as f64 * CHAR_WIDTH + 2.0 * NODE_PADDING
+}
+
+/// The point where the line from the center of a node towards `to` leaves the node's box
+fn clip(from: (f64, f64), to: (f64, f64), width: f64) -> (f64, f64) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let scale = f64::min(
+        if dx == 0.0 {
+            f64::INFINITY
+        } else {
+            width / 2.0 / dx.abs()
+        },
+        if dy == 0.0 {
+            f64::INFINITY
+        } else {
+            NODE_HEIGHT / 2.0 / dy.abs()
+        },
+    );
+    (from.0 + dx * scale, from.1 + dy * scale)
+}
+
+fn href(typ: &schema::FullType) -> String {
+    format!("{}.{}.html", typ.kind.prefix(), typ.name)
+}
+
+/// The same colors the pages use for links to each kind of type
+fn color(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Scalar => "#ff7f00",
+        Kind::Object => "#2dbfb8",
+        Kind::InputObject => "#09bd00",
+        Kind::Interface => "#b78cf2",
+        Kind::Union => "#43aec7",
+        Kind::Enum => "#82b089",
+        Kind::List | Kind::NonNull => "#999",
+    }
+}
+
+/// Mermaid treats `end` as a keyword in any case, so it can't be used as a node ID
+fn mermaid_id(name: &str) -> String {
+    if name.eq_ignore_ascii_case("end") {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_neighborhood() {
+        let schema = parse_schema(
+            r#"
+            type Query { me: User search: [Result] }
+            interface Node { id: ID! }
+            type User implements Node { id: ID! posts: [Post] }
+            type Post implements Node { id: ID! author: User title: String }
+            union Result = User | Post
+            type Unrelated { name: String }
+            "#,
+        )
+        .unwrap();
+        let relationships = Relationships::new(&schema);
+
+        let graph = relationships.neighborhood("User", 1);
+        let nodes: Vec<(&str, usize)> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.typ.name.as_str(), node.distance))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("User", 0),
+                ("Post", 1),
+                ("Query", 1),
+                ("Result", 1),
+                ("Node", 1)
+            ]
+        );
+
+        assert_eq!(
+            graph
+                .to_dot()
+                .lines()
+                .filter(|l| l.contains("->"))
+                .collect::<Vec<_>>(),
+            vec![
+                r#"    "Post" -> "Node" [style=dashed, arrowhead=empty];"#,
+                r#"    "Post" -> "User" [label="author"];"#,
+                r#"    "Query" -> "Result" [label="search"];"#,
+                r#"    "Query" -> "User" [label="me"];"#,
+                r#"    "Result" -> "Post" [style=dotted];"#,
+                r#"    "Result" -> "User" [style=dotted];"#,
+                r#"    "User" -> "Node" [style=dashed, arrowhead=empty];"#,
+                r#"    "User" -> "Post" [label="posts"];"#,
+            ]
+        );
+
+        let whole = relationships.whole();
+        assert_eq!(whole.nodes.len(), 6);
+        assert!(whole
+            .to_mermaid()
+            .contains("    Unrelated[\"Unrelated\"]:::object\n"));
+        assert_eq!(whole.to_svg().matches("<a href=").count(), 6);
+    }
+}
@@ -1,49 +1,61 @@
-use super::schema;
+use super::{history, schema};
 use handlebars::{
     Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
 };
+use std::collections::{HashMap, HashSet};
 
+/// Renders a type reference, linking to the named type unless it was left out of the
+/// documentation.
 pub struct TypeRefRenderer<'a> {
-    _schema: &'a schema::Schema,
+    documented: HashSet<&'a str>,
 }
 
 impl<'a> TypeRefRenderer<'a> {
     pub fn new(schema: &'a schema::Schema) -> Self {
-        Self { _schema: schema }
+        Self {
+            documented: schema.types.iter().map(|typ| typ.name.as_str()).collect(),
+        }
     }
 
     pub fn render_type_ref(
         &self,
         type_ref: &schema::TypeRef,
         out: &mut dyn Output,
-    ) -> Result<(), std::io::Error> {
+    ) -> Result<(), RenderError> {
+        let of_type = || {
+            type_ref
+                .of_type
+                .as_deref()
+                .ok_or_else(|| RenderError::new("Truncated type reference"))
+        };
+
         match &type_ref.kind {
             schema::Kind::List => {
                 out.write("[")?;
-                if let Some(ref of_type) = type_ref.of_type {
-                    self.render_type_ref(of_type, out)?;
-                } else {
-                    out.write("?")?;
-                }
+                self.render_type_ref(of_type()?, out)?;
                 out.write("]")?;
             }
             schema::Kind::NonNull => {
-                if let Some(ref of_type) = type_ref.of_type {
-                    self.render_type_ref(of_type, out)?;
-                } else {
-                    out.write("?")?;
-                }
+                self.render_type_ref(of_type()?, out)?;
                 out.write("!")?;
             }
             k => {
-                let o = format!(
-                    r#"<a class="{}" href="{}.{}.html">{}</a>"#,
-                    k.prefix(),
-                    k.prefix(),
-                    type_ref.name.as_deref().unwrap(),
-                    type_ref.name.as_deref().unwrap()
-                );
-                out.write(&o)?;
+                let name = type_ref
+                    .name
+                    .as_deref()
+                    .ok_or_else(|| RenderError::new("Named type reference without a name"))?;
+                if self.documented.contains(name) {
+                    let o = format!(
+                        r#"<a class="{}" href="{}.{}.html">{}</a>"#,
+                        k.prefix(),
+                        k.prefix(),
+                        name,
+                        name
+                    );
+                    out.write(&o)?;
+                } else {
+                    out.write(name)?;
+                }
             }
         }
 
@@ -70,6 +82,164 @@ impl HelperDef for TypeRefRenderer<'_> {
     }
 }
 
+/// Renders the label of the snapshot that a field, argument or enum value first appeared in.
+///
+/// Takes the type name, the name of the field, input field or enum value, and optionally the name
+/// of an argument.
+pub struct SinceRenderer<'a> {
+    since: Option<&'a history::Since>,
+}
+
+impl<'a> SinceRenderer<'a> {
+    pub fn new(since: Option<&'a history::Since>) -> Self {
+        Self { since }
+    }
+}
+
+impl HelperDef for SinceRenderer<'_> {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper,
+        _: &Handlebars,
+        _: &Context,
+        _: &mut RenderContext,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let since = match self.since {
+            Some(since) => since,
+            None => return Ok(()),
+        };
+
+        let params = h
+            .params()
+            .iter()
+            .map(|param| {
+                param
+                    .value()
+                    .as_str()
+                    .ok_or_else(|| RenderError::new("Parameters to since must be strings"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let coordinate = match params.as_slice() {
+            [parent, name] => format!("{}.{}", parent, name),
+            [parent, name, arg] => format!("{}.{}({}:)", parent, name, arg),
+            _ => return Err(RenderError::new("since takes two or three parameters")),
+        };
+
+        let label = match (since.first_seen(&coordinate), since.latest()) {
+            (Some(label), _) => format!("Since {}", label),
+            (None, Some(latest)) => format!("Added after {}", latest),
+            (None, None) => return Ok(()),
+        };
+        out.write(&format!(
+            r#" <span class="since">{}</span>"#,
+            handlebars::html_escape(&label)
+        ))?;
+        Ok(())
+    }
+}
+
+/// Renders links to the fields of the interfaces that a field satisfies.
+///
+/// Takes the name of the object or interface and the name of the field.
+pub struct SatisfiesRenderer<'a> {
+    types: HashMap<&'a str, &'a schema::FullType>,
+}
+
+impl<'a> SatisfiesRenderer<'a> {
+    pub fn new(schema: &'a schema::Schema) -> Self {
+        Self {
+            types: schema
+                .types
+                .iter()
+                .map(|typ| (typ.name.as_str(), typ))
+                .collect(),
+        }
+    }
+
+    /// The interfaces the type implements, its own first and then the ones they implement in
+    /// turn
+    fn interfaces(&self, typ: &schema::FullType) -> Vec<&'a schema::FullType> {
+        let mut interfaces: Vec<&'a schema::FullType> = Vec::new();
+        let mut next = 0;
+        let mut implemented_by = typ;
+        loop {
+            for interface in implemented_by.interfaces.iter().flatten() {
+                let interface = interface
+                    .name
+                    .as_deref()
+                    .and_then(|name| self.types.get(name));
+                if let Some(interface) = interface {
+                    if !interfaces.iter().any(|known| known.name == interface.name) {
+                        interfaces.push(interface);
+                    }
+                }
+            }
+            match interfaces.get(next) {
+                Some(interface) => implemented_by = interface,
+                None => return interfaces,
+            }
+            next += 1;
+        }
+    }
+}
+
+impl HelperDef for SatisfiesRenderer<'_> {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper,
+        _: &Handlebars,
+        _: &Context,
+        _: &mut RenderContext,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let params = h
+            .params()
+            .iter()
+            .map(|param| {
+                param
+                    .value()
+                    .as_str()
+                    .ok_or_else(|| RenderError::new("Parameters to satisfies must be strings"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (type_name, field_name) = match params.as_slice() {
+            [type_name, field_name] => (*type_name, *field_name),
+            _ => return Err(RenderError::new("satisfies takes two parameters")),
+        };
+
+        let typ = match self.types.get(type_name) {
+            Some(typ) => typ,
+            None => return Ok(()),
+        };
+        let links: Vec<String> = self
+            .interfaces(typ)
+            .into_iter()
+            .filter(|interface| {
+                interface
+                    .fields
+                    .iter()
+                    .flatten()
+                    .any(|field| field.name == field_name)
+            })
+            .map(|interface| {
+                format!(
+                    r#"<a class="interface" href="interface.{}.html#field.{}">{}</a>"#,
+                    interface.name, field_name, interface.name
+                )
+            })
+            .collect();
+
+        if !links.is_empty() {
+            out.write(&format!(
+                r#" <span class="satisfies">from {}</span>"#,
+                links.join(", ")
+            ))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Default)]
 pub struct Docblock;
 
@@ -119,7 +289,70 @@ impl HelperDef for Kind {
 
         let kind: schema::Kind = serde_json::from_value(param.value().clone())?;
 
-        out.write(&kind.prefix())?;
+        out.write(kind.prefix())?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_satisfies_and_implements() {
+        let schema = parse_schema(
+            r#"
+            type Query { file: File }
+            interface Node { id: ID! }
+            interface Resource implements Node { id: ID! url: String }
+            type File implements Resource { id: ID! url: String size: Int }
+            "#,
+        )
+        .unwrap();
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(true);
+        handlebars.register_helper("t", Box::new(TypeRefRenderer::new(&schema)));
+        handlebars.register_helper("satisfies", Box::new(SatisfiesRenderer::new(&schema)));
+        handlebars
+            .register_template_string(
+                "implements",
+                include_str!("templates/partials/implements.handlebars"),
+            )
+            .unwrap();
+        let render = |template: &str, data: serde_json::Value| {
+            handlebars.render_template(template, &data).unwrap()
+        };
+
+        // A field can come from an interface that is only implemented through another interface
+        assert_eq!(
+            render(r#"{{ satisfies "File" "id" }}"#, serde_json::json!({})),
+            concat!(
+                r#" <span class="satisfies">from "#,
+                r#"<a class="interface" href="interface.Resource.html#field.id">Resource</a>, "#,
+                r#"<a class="interface" href="interface.Node.html#field.id">Node</a></span>"#,
+            )
+        );
+        assert_eq!(
+            render(r#"{{ satisfies "Resource" "id" }}"#, serde_json::json!({})),
+            r#" <span class="satisfies">from <a class="interface" href="interface.Node.html#field.id">Node</a></span>"#
+        );
+        assert_eq!(
+            render(r#"{{ satisfies "File" "size" }}"#, serde_json::json!({})),
+            ""
+        );
+
+        let implements = |name: &str| {
+            let typ = schema.types.iter().find(|typ| typ.name == name).unwrap();
+            render(
+                "{{> implements interfaces=interfaces }}",
+                serde_json::json!({ "interfaces": typ.interfaces }),
+            )
+        };
+        assert!(implements("File").contains(
+            r#"<code><a class="interface" href="interface.Resource.html">Resource</a></code>"#
+        ));
+        assert!(implements("Resource")
+            .contains(r#"<code><a class="interface" href="interface.Node.html">Node</a></code>"#));
+    }
+}
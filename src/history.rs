@@ -0,0 +1,196 @@
+//! The history of a schema, reconstructed from a series of snapshots.
+//!
+//! Consecutive snapshots are compared with [`diff::Diff`], so every entry in the history is the
+//! set of changes that appeared between one snapshot and the next.
+
+use super::{diff, schema};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A version of the schema as it was at some point in time
+pub struct Snapshot {
+    /// A date or version name identifying the snapshot
+    pub label: String,
+    pub schema: schema::Schema,
+}
+
+/// The changes between consecutive snapshots, oldest first
+pub struct History {
+    entries: Vec<Entry>,
+}
+
+/// The changes that first appeared in one snapshot
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub label: String,
+    pub changes: Vec<diff::Change>,
+}
+
+/// The changes to a single type that first appeared in one snapshot
+#[derive(Debug, Serialize)]
+pub struct TypeEntry<'a> {
+    pub label: &'a str,
+    pub changes: Vec<&'a diff::Change>,
+}
+
+impl History {
+    /// Build the history from snapshots sorted oldest first, followed by the current schema.
+    ///
+    /// Snapshots without any changes compared to the one before them are left out.
+    pub fn new(snapshots: &[Snapshot], current: &Snapshot) -> Self {
+        let entries = snapshots
+            .iter()
+            .chain(std::iter::once(current))
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| Entry {
+                label: pair[1].label.clone(),
+                changes: diff::Diff::new(&pair[0].schema, &pair[1].schema).changes,
+            })
+            .filter(|entry| !entry.changes.is_empty())
+            .collect();
+
+        Self { entries }
+    }
+
+    /// All entries, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().rev()
+    }
+
+    /// The entries that changed the given type (or `@directive`), newest first
+    pub fn for_type(&self, name: &str) -> Vec<TypeEntry<'_>> {
+        self.entries()
+            .filter_map(|entry| {
+                let changes: Vec<&diff::Change> = entry
+                    .changes
+                    .iter()
+                    .filter(|change| change.type_name() == name)
+                    .collect();
+                if changes.is_empty() {
+                    None
+                } else {
+                    Some(TypeEntry {
+                        label: &entry.label,
+                        changes,
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+/// The snapshot in which each field, argument, input field and enum value first appeared
+pub struct Since {
+    labels: Vec<String>,
+    first_seen: HashMap<String, usize>,
+}
+
+impl Since {
+    /// Record where every element first appeared in snapshots sorted oldest first.
+    pub fn new(snapshots: &[Snapshot]) -> Self {
+        let mut first_seen = HashMap::new();
+        for (i, snapshot) in snapshots.iter().enumerate() {
+            for coordinate in coordinates(&snapshot.schema) {
+                first_seen.entry(coordinate).or_insert(i);
+            }
+        }
+
+        Self {
+            labels: snapshots.iter().map(|s| s.label.clone()).collect(),
+            first_seen,
+        }
+    }
+
+    /// The label of the snapshot that the element with the given schema coordinate first
+    /// appeared in, or `None` if it is newer than all of them
+    pub fn first_seen(&self, coordinate: &str) -> Option<&str> {
+        self.first_seen
+            .get(coordinate)
+            .map(|i| self.labels[*i].as_str())
+    }
+
+    /// The label of the newest snapshot
+    pub fn latest(&self) -> Option<&str> {
+        self.labels.last().map(String::as_str)
+    }
+}
+
+/// The schema coordinates (`Type.field`, `Type.field(arg:)`, `Enum.VALUE`) of the elements in a
+/// schema that can be annotated with the snapshot they first appeared in
+fn coordinates(schema: &schema::Schema) -> Vec<String> {
+    let mut coordinates = Vec::new();
+    for typ in &schema.types {
+        for field in typ.fields.iter().flatten() {
+            coordinates.push(format!("{}.{}", typ.name, field.name));
+            for arg in &field.args {
+                coordinates.push(format!("{}.{}({}:)", typ.name, field.name, arg.name));
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            coordinates.push(format!("{}.{}", typ.name, input_field.name));
+        }
+        for enum_value in typ.enum_values.iter().flatten() {
+            coordinates.push(format!("{}.{}", typ.name, enum_value.name));
+        }
+    }
+    coordinates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_history_for_type() {
+        let snapshot = |label: &str, sdl: &str| Snapshot {
+            label: label.to_string(),
+            schema: parse_schema(sdl).unwrap(),
+        };
+        let snapshots = vec![
+            snapshot("2021-01-01", "type Query { a: String }"),
+            snapshot("2021-02-01", "type Query { a: String }"),
+            snapshot("2021-03-01", "type Query { a: String b: String }"),
+        ];
+        let current = snapshot(
+            "2021-04-01",
+            "type Query { a: String @deprecated b: String } type User { id: ID }",
+        );
+
+        let history = History::new(&snapshots, &current);
+        let labels: Vec<&str> = history.entries().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, vec!["2021-04-01", "2021-03-01"]);
+
+        let query: Vec<(&str, Vec<&str>)> = history
+            .for_type("Query")
+            .iter()
+            .map(|e| (e.label, e.changes.iter().map(|c| c.kind).collect()))
+            .collect();
+        assert_eq!(
+            query,
+            vec![
+                ("2021-04-01", vec!["deprecation_added"]),
+                ("2021-03-01", vec!["field_added"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_since() {
+        let snapshot = |label: &str, sdl: &str| Snapshot {
+            label: label.to_string(),
+            schema: parse_schema(sdl).unwrap(),
+        };
+        let since = Since::new(&[
+            snapshot("v1", "type Query { a: String }"),
+            snapshot("v2", "type Query { a(x: Int): String b: String }"),
+        ]);
+
+        assert_eq!(since.first_seen("Query.a"), Some("v1"));
+        assert_eq!(since.first_seen("Query.a(x:)"), Some("v2"));
+        assert_eq!(since.first_seen("Query.b"), Some("v2"));
+        assert_eq!(since.first_seen("Query.c"), None);
+        assert_eq!(since.latest(), Some("v2"));
+    }
+}
@@ -0,0 +1,359 @@
+use super::GraphqlRequest;
+use serde_json::Value;
+
+/// A small query that asks the server which introspection fields and arguments it supports.
+const PROBE_QUERY: &str = include_str!("introspection_probe_query.graphql");
+
+/// The optional parts of the introspection schema that a server may or may not support.
+///
+/// The default value describes a server that only supports the introspection schema of the June
+/// 2018 GraphQL specification, which every server should be able to answer.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `__Schema.description`
+    pub schema_description: bool,
+    /// The name of the field on `__Type` that exposes the specification URL of a scalar. Older
+    /// drafts of the specification spelled it `specifiedByUrl`.
+    pub specified_by_url: Option<&'static str>,
+    /// `__Type.isOneOf`
+    pub is_one_of: bool,
+    /// `__Directive.isRepeatable`
+    pub is_repeatable: bool,
+    /// `__InputValue.isDeprecated` and `__Field.args(includeDeprecated:)`
+    pub deprecated_args: bool,
+    /// `__InputValue.isDeprecated` and `__Type.inputFields(includeDeprecated:)`
+    pub deprecated_input_fields: bool,
+    /// `__InputValue.isDeprecated` and `__Directive.args(includeDeprecated:)`
+    pub deprecated_directive_args: bool,
+}
+
+impl Capabilities {
+    /// The request that finds out which capabilities the server has.
+    pub fn probe_request() -> GraphqlRequest {
+        GraphqlRequest {
+            query: PROBE_QUERY.into(),
+            operation_name: "IntrospectionCapabilities".into(),
+        }
+    }
+
+    /// Interpret the response to the probe request.
+    ///
+    /// Returns `None` if the response does not look like an answer to the probe request.
+    pub fn from_probe_response(response: &Value) -> Option<Self> {
+        let data = response.get("data")?;
+        let schema = Probe::new(data.get("schema")?)?;
+        let typ = Probe::new(data.get("type")?)?;
+        let field = Probe::new(data.get("field")?)?;
+        let directive = Probe::new(data.get("directive")?)?;
+        let input_value = Probe::new(data.get("inputValue")?)?;
+
+        let input_value_deprecation =
+            input_value.has_field("isDeprecated") && input_value.has_field("deprecationReason");
+
+        Some(Self {
+            schema_description: schema.has_field("description"),
+            specified_by_url: if typ.has_field("specifiedByURL") {
+                Some("specifiedByURL")
+            } else if typ.has_field("specifiedByUrl") {
+                Some("specifiedByUrl")
+            } else {
+                None
+            },
+            is_one_of: typ.has_field("isOneOf"),
+            is_repeatable: directive.has_field("isRepeatable"),
+            deprecated_args: input_value_deprecation && field.has_arg("args", "includeDeprecated"),
+            deprecated_input_fields: input_value_deprecation
+                && typ.has_arg("inputFields", "includeDeprecated"),
+            deprecated_directive_args: input_value_deprecation
+                && directive.has_arg("args", "includeDeprecated"),
+        })
+    }
+
+    /// A human readable list of the optional features that were detected
+    pub fn describe(&self) -> String {
+        let mut features = Vec::new();
+        if self.schema_description {
+            features.push("schema description");
+        }
+        if let Some(specified_by_url) = self.specified_by_url {
+            features.push(specified_by_url);
+        }
+        if self.is_one_of {
+            features.push("isOneOf");
+        }
+        if self.is_repeatable {
+            features.push("isRepeatable");
+        }
+        if self.deprecated_args {
+            features.push("deprecated arguments");
+        }
+        if self.deprecated_input_fields {
+            features.push("deprecated input fields");
+        }
+        if self.deprecated_directive_args {
+            features.push("deprecated directive arguments");
+        }
+
+        if features.is_empty() {
+            "none".to_string()
+        } else {
+            features.join(", ")
+        }
+    }
+
+    /// The richest introspection request that the server supports, with type references nested
+    /// `type_ref_depth` levels deep.
+    pub fn introspection_request(&self, type_ref_depth: usize) -> GraphqlRequest {
+        let query = format!(
+            r#"query IntrospectionQuery {{
+  __schema {{
+    {schema_description}queryType {{ name }}
+    mutationType {{ name }}
+    subscriptionType {{ name }}
+    types {{
+      ...FullType
+    }}
+    directives {{
+      name
+      description
+      locations
+      {is_repeatable}args{directive_args} {{
+        ...InputValue
+      }}
+    }}
+  }}
+}}
+{fragments}"#,
+            schema_description = optional(self.schema_description, "description\n    "),
+            is_repeatable = optional(self.is_repeatable, "isRepeatable\n      "),
+            directive_args = include_deprecated(self.deprecated_directive_args),
+            fragments = self.fragments(type_ref_depth),
+        );
+
+        GraphqlRequest {
+            query: query.into(),
+            operation_name: "IntrospectionQuery".into(),
+        }
+    }
+
+    /// A request for a single named type, with type references nested `type_ref_depth` levels
+    /// deep.
+    ///
+    /// Used to fetch a type again when its type references did not fit in the main introspection
+    /// query.
+    pub fn type_request(&self, name: &str, type_ref_depth: usize) -> GraphqlRequest {
+        let query = format!(
+            r#"query IntrospectionType {{
+  __type(name: {name}) {{
+    ...FullType
+  }}
+}}
+{fragments}"#,
+            name = Value::from(name),
+            fragments = self.fragments(type_ref_depth),
+        );
+
+        GraphqlRequest {
+            query: query.into(),
+            operation_name: "IntrospectionType".into(),
+        }
+    }
+
+    /// The `FullType`, `InputValue` and `TypeRef` fragments shared by the introspection requests
+    fn fragments(&self, type_ref_depth: usize) -> String {
+        format!(
+            r#"fragment FullType on __Type {{
+  kind
+  name
+  description
+  {specified_by_url}{is_one_of}fields(includeDeprecated: true) {{
+    name
+    description
+    args{field_args} {{
+      ...InputValue
+    }}
+    type {{
+      ...TypeRef
+    }}
+    isDeprecated
+    deprecationReason
+  }}
+  inputFields{input_fields} {{
+    ...InputValue
+  }}
+  interfaces {{
+    ...TypeRef
+  }}
+  enumValues(includeDeprecated: true) {{
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }}
+  possibleTypes {{
+    ...TypeRef
+  }}
+}}
+fragment InputValue on __InputValue {{
+  name
+  description
+  type {{ ...TypeRef }}
+  defaultValue
+{input_value_deprecation}}}
+fragment TypeRef on __Type {{
+{type_ref}}}
+"#,
+            specified_by_url = match self.specified_by_url {
+                Some("specifiedByUrl") => "specifiedByURL: specifiedByUrl\n  ",
+                Some(_) => "specifiedByURL\n  ",
+                None => "",
+            },
+            is_one_of = optional(self.is_one_of, "isOneOf\n  "),
+            field_args = include_deprecated(self.deprecated_args),
+            input_fields = include_deprecated(self.deprecated_input_fields),
+            input_value_deprecation = optional(
+                self.deprecated_args
+                    || self.deprecated_input_fields
+                    || self.deprecated_directive_args,
+                "  isDeprecated\n  deprecationReason\n"
+            ),
+            type_ref = type_ref_selection(type_ref_depth),
+        )
+    }
+}
+
+fn include_deprecated(enabled: bool) -> &'static str {
+    if enabled {
+        "(includeDeprecated: true)"
+    } else {
+        ""
+    }
+}
+
+fn optional(enabled: bool, field: &'static str) -> &'static str {
+    if enabled {
+        field
+    } else {
+        ""
+    }
+}
+
+/// The body of the `TypeRef` fragment: `kind` and `name`, nested through `ofType` until there are
+/// `depth` levels.
+fn type_ref_selection(depth: usize) -> String {
+    let mut selection = String::new();
+    for level in 0..depth.max(1) {
+        let indent = "  ".repeat(level + 1);
+        if level > 0 {
+            selection.push_str(&format!("{}ofType {{\n", "  ".repeat(level)));
+        }
+        selection.push_str(&format!("{0}kind\n{0}name\n", indent));
+    }
+    for level in (1..depth.max(1)).rev() {
+        selection.push_str(&format!("{}}}\n", "  ".repeat(level)));
+    }
+    selection
+}
+
+/// The fields (and their arguments) of one of the introspection types, as returned by the probe
+/// query
+struct Probe<'a>(&'a [Value]);
+
+impl<'a> Probe<'a> {
+    fn new(typ: &'a Value) -> Option<Self> {
+        let fields = typ.get("fields")?.as_array()?;
+        Some(Self(fields))
+    }
+
+    fn field(&self, name: &str) -> Option<&'a Value> {
+        self.0
+            .iter()
+            .find(|field| field.get("name").and_then(Value::as_str) == Some(name))
+    }
+
+    fn has_field(&self, name: &str) -> bool {
+        self.field(name).is_some()
+    }
+
+    fn has_arg(&self, field: &str, arg: &str) -> bool {
+        self.field(field)
+            .and_then(|field| field.get("args"))
+            .and_then(Value::as_array)
+            .is_some_and(|args| {
+                args.iter()
+                    .any(|a| a.get("name").and_then(Value::as_str) == Some(arg))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_probe_response() {
+        let fields = |names: &[&str]| -> Value {
+            json!({
+                "fields": names
+                    .iter()
+                    .map(|name| match *name {
+                        "args" | "inputFields" => json!({
+                            "name": name,
+                            "args": [{ "name": "includeDeprecated" }],
+                        }),
+                        _ => json!({ "name": name, "args": [] }),
+                    })
+                    .collect::<Vec<_>>(),
+            })
+        };
+        let response = json!({
+            "data": {
+                "schema": fields(&["types", "queryType"]),
+                "type": fields(&["kind", "name", "inputFields", "specifiedByUrl"]),
+                "field": fields(&["name", "args"]),
+                "directive": fields(&["name", "locations"]),
+                "inputValue": fields(&["name", "isDeprecated", "deprecationReason"]),
+            }
+        });
+
+        let capabilities = Capabilities::from_probe_response(&response).unwrap();
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                specified_by_url: Some("specifiedByUrl"),
+                deprecated_args: true,
+                deprecated_input_fields: true,
+                ..Capabilities::default()
+            }
+        );
+
+        let query = capabilities.introspection_request(8).query;
+        assert!(query.contains("specifiedByURL: specifiedByUrl"));
+        assert!(query.contains("inputFields(includeDeprecated: true)"));
+        assert!(!query.contains("isRepeatable"));
+
+        assert!(Capabilities::from_probe_response(&json!({ "data": null })).is_none());
+    }
+
+    #[test]
+    fn test_basic_introspection_request() {
+        // Without capabilities, the query must be the one every server answers, which asks for
+        // no deprecated arguments or input fields
+        let query = Capabilities::default().introspection_request(8).query;
+        assert_eq!(query, crate::runtime::INTROSPECTION_QUERY);
+        let input_value = query.split("fragment InputValue").nth(1).unwrap();
+        assert!(!input_value.contains("isDeprecated"));
+        assert!(!query.contains("args(includeDeprecated: true)"));
+        assert!(!query.contains("inputFields(includeDeprecated: true)"));
+    }
+
+    #[test]
+    fn test_type_ref_selection() {
+        assert_eq!(type_ref_selection(1), "  kind\n  name\n");
+        assert_eq!(
+            type_ref_selection(3),
+            "  kind\n  name\n  ofType {\n    kind\n    name\n    ofType {\n      kind\n      name\n    }\n  }\n"
+        );
+    }
+}
@@ -58,37 +58,98 @@
 //!
 //! ```text
 //! USAGE:
-//!     docql [OPTIONS] --output <path> <--endpoint <url>|--schema <path>>
+//!     docql [FLAGS] [OPTIONS] --output <path> <--endpoint <url>|--schema <path>|--sdl <path>>
+//!     docql <SUBCOMMAND>
 //!
 //! FLAGS:
-//!     -h, --help       Prints help information
-//!     -V, --version    Prints version information
+//!         --exclude-orphans     Leave types that can't be reached from the root operation types out of the documentation
+//!     -h, --help                Prints help information
+//!         --hide-connections    Leave Relay connection and edge types out of the index and search
+//!     -V, --version             Prints version information
 //!
 //! OPTIONS:
-//!     -e, --endpoint <url>        The URL of the GraphQL endpoint to document
-//!     -x, --header <header>...    Additional headers when executing the GraphQL introspection query (e.g. `-x
-//!                                 "Authorization: Bearer abcdef"`
-//!     -n, --name <name>           The name to give to the schema (used in the title of the page) [default: GraphQL Schema]
-//!     -o, --output <path>         The directory to put the generated documentation
-//!     -s, --schema <path>         The output of a GraphQL introspection query already stored locally
+//!     -e, --endpoint <url>                The URL of the GraphQL endpoint to document
+//!         --exclude-field <pattern>...    Leave out fields and input fields whose `Type.field` path matches one of these
+//!                                         globs (or /regular expressions/), like `*.internal*`
+//!         --exclude-type <pattern>...     Leave out types whose name matches one of these globs (or /regular
+//!                                         expressions/), like `Internal*` or `__*`
+//!         --graph <format>...             Also write a graph of how all types refer to each other in this format to the
+//!                                         output directory [possible values: dot, mermaid]
+//!         --graph-depth <hops>            How many references away from each type the graph on its page reaches (0 for no
+//!                                         graph) [default: 1]
+//!     -x, --header <header>...            Additional headers when executing the GraphQL introspection query (e.g. `-x
+//!                                         "Authorization: Bearer abcdef"`
+//!         --history <path>                A directory of earlier schema snapshots (introspection JSON or SDL) whose file
+//!                                         names start with their date, like 2021-03-01.json, used to generate a changelog
+//!         --include-field <pattern>...    Only document fields and input fields whose `Type.field` path matches one of
+//!                                         these globs (or /regular expressions/)
+//!         --include-type <pattern>...     Only document types whose name matches one of these globs (or /regular
+//!                                         expressions/)
+//!         --min-coverage <percent>        Fail after writing the documentation if less than this percentage of types,
+//!                                         fields, arguments, input fields and enum values have a description
+//!     -n, --name <name>                   The name to give to the schema (used in the title of the page) [default: GraphQL
+//!                                         Schema]
+//!     -o, --output <path>                 The directory to put the generated documentation
+//!         --save-schema <path>...         Also save the schema to this file, as introspection JSON or, for paths ending in
+//!                                         .graphql, .graphqls or .gql, as SDL (can be given more than once)
+//!     -s, --schema <path>                 The output of a GraphQL introspection query already stored locally (files ending
+//!                                         in .gz are decompressed, files ending in .graphql, .graphqls or .gql are read as
+//!                                         SDL)
+//!         --sdl <path>                    A GraphQL schema definition language (SDL) file already stored locally
+//!         --since <label=path>...         An earlier version of the schema as label=path, like v1=old.json; fields,
+//!                                         arguments and enum values are labelled with the first version they appear in
+//!                                         (give the oldest version first)
+//!         --stats <format>                Also write the statistics shown on stats.html in this format to the output
+//!                                         directory [possible values: json]
+//!         --type-ref-depth <depth>        How many levels of list and non-null wrappers the introspection query asks for;
+//!                                         types that are nested deeper are fetched again on their own [default: 8]
+//!
+//! SUBCOMMANDS:
+//!     diff     Compare two versions of a schema and classify the changes as breaking, dangerous or safe
+//!     graph    Print a graph of how the types in a schema refer to each other
+//!     help     Prints this message or the help of the given subcommand(s)
+//!     lint     Check a schema against naming and documentation conventions
 //! ```
 #![deny(missing_docs)]
 use chrono::NaiveDate;
-use clap::{App, AppSettings, Arg, ArgGroup};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
 use futures::stream::{StreamExt as _, TryStreamExt as _};
 use std::collections::HashMap;
+use std::io::Read as _;
 
+#[doc(hidden)]
+pub mod bench;
+mod coverage;
+mod diff;
 mod error;
+mod filter;
+mod graph;
 mod handlebars_helpers;
+mod history;
+mod introspection;
+mod lint;
+mod paths;
+mod reachability;
+mod relay;
 mod renderer;
 mod runtime;
 mod schema;
+mod sdl;
 mod search_index;
+mod stats;
+mod validation;
 pub use error::{Error, Result};
+use introspection::Capabilities;
 use renderer::Renderer;
 pub use runtime::{GraphqlRequest, Runtime, GRAPHQL_REQUEST, INTROSPECTION_QUERY};
 
 static USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+/// Schema files with these extensions are parsed as SDL instead of introspection JSON
+static SDL_EXTENSIONS: &[&str] = &[".graphql", ".graphqls", ".gql"];
+/// How many levels the `TypeRef` fragment of the introspection query has unless configured
+const DEFAULT_TYPE_REF_DEPTH: usize = 8;
+/// The deepest `TypeRef` fragment that will be used when fetching types again
+const MAX_TYPE_REF_DEPTH: usize = 64;
 
 /// The primary entrypoint to run the application.
 ///
@@ -104,6 +165,8 @@ pub async fn main(runtime: impl Runtime) -> Result<()> {
         .version(env!("CARGO_PKG_VERSION"))
         .about("Generate documentation for a GraphQL API")
         .setting(AppSettings::NoBinaryName)
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .setting(AppSettings::ArgsNegateSubcommands)
         .arg(
             Arg::with_name("endpoint")
                 .short("e")
@@ -127,7 +190,14 @@ pub async fn main(runtime: impl Runtime) -> Result<()> {
                 .short("s")
                 .long("schema")
                 .alias("schema-file")
-                .help("The output of a GraphQL introspection query already stored locally")
+                .help("The output of a GraphQL introspection query already stored locally (files ending in .gz are decompressed, files ending in .graphql, .graphqls or .gql are read as SDL)")
+                .takes_value(true)
+                .value_name("path")
+        )
+        .arg(
+            Arg::with_name("sdl")
+                .long("sdl")
+                .help("A GraphQL schema definition language (SDL) file already stored locally")
                 .takes_value(true)
                 .value_name("path")
         )
@@ -156,20 +226,261 @@ pub async fn main(runtime: impl Runtime) -> Result<()> {
                 .number_of_values(1)
                 .multiple(true)
                 .takes_value(true)
-                .conflicts_with("schema")
+                .conflicts_with_all(&["schema", "sdl"])
                 .validator(|s| {
                     let mut parts = s.splitn(2, ":").skip(1);
                     parts.next().ok_or_else(|| "Header must include a name, a colon, and a value".to_string())?;
                     Ok(())
                 })
         )
+        .arg(
+            Arg::with_name("type-ref-depth")
+                .long("type-ref-depth")
+                .help("How many levels of list and non-null wrappers the introspection query asks for; types that are nested deeper are fetched again on their own [default: 8]")
+                .takes_value(true)
+                .value_name("depth")
+                .conflicts_with_all(&["schema", "sdl"])
+                .validator(|s| match s.parse::<usize>() {
+                    Ok(depth) if depth > 0 => Ok(()),
+                    _ => Err("Depth must be a positive number".to_string()),
+                })
+        )
+        .arg(
+            Arg::with_name("save-schema")
+                .long("save-schema")
+                .help("Also save the schema to this file, as introspection JSON or, for paths ending in .graphql, .graphqls or .gql, as SDL (can be given more than once)")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("path")
+        )
+        .arg(
+            Arg::with_name("history")
+                .long("history")
+                .help("A directory of earlier schema snapshots (introspection JSON or SDL) whose file names start with their date, like 2021-03-01.json, used to generate a changelog")
+                .takes_value(true)
+                .value_name("path")
+        )
+        .arg(
+            Arg::with_name("since")
+                .long("since")
+                .help("An earlier version of the schema as label=path, like v1=old.json; fields, arguments and enum values are labelled with the first version they appear in (give the oldest version first)")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("label=path")
+                .validator(|s| {
+                    match s.splitn(2, '=').collect::<Vec<_>>().as_slice() {
+                        [label, path] if !label.is_empty() && !path.is_empty() => Ok(()),
+                        _ => Err("Must be a label and a path separated by `=`".to_string()),
+                    }
+                })
+        )
+        .arg(
+            Arg::with_name("stats")
+                .long("stats")
+                .help("Also write the statistics shown on stats.html in this format to the output directory")
+                .takes_value(true)
+                .value_name("format")
+                .possible_values(&["json"])
+        )
+        .arg(
+            Arg::with_name("exclude-orphans")
+                .long("exclude-orphans")
+                .help("Leave types that can't be reached from the root operation types out of the documentation")
+        )
+        .arg(
+            Arg::with_name("graph")
+                .long("graph")
+                .help("Also write a graph of how all types refer to each other in this format to the output directory")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("format")
+                .possible_values(&["dot", "mermaid"])
+        )
+        .arg(
+            Arg::with_name("graph-depth")
+                .long("graph-depth")
+                .help("How many references away from each type the graph on its page reaches (0 for no graph)")
+                .takes_value(true)
+                .value_name("hops")
+                .default_value("1")
+                .validator(|s| s.parse::<usize>().map(|_| ()).map_err(|_| "Must be a whole number".to_string()))
+        )
+        .arg(
+            Arg::with_name("include-type")
+                .long("include-type")
+                .help("Only document types whose name matches one of these globs (or /regular expressions/)")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("pattern")
+                .validator(|s| filter::Pattern::parse(&s).map(|_| ()))
+        )
+        .arg(
+            Arg::with_name("exclude-type")
+                .long("exclude-type")
+                .help("Leave out types whose name matches one of these globs (or /regular expressions/), like `Internal*` or `__*`")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("pattern")
+                .validator(|s| filter::Pattern::parse(&s).map(|_| ()))
+        )
+        .arg(
+            Arg::with_name("include-field")
+                .long("include-field")
+                .help("Only document fields and input fields whose `Type.field` path matches one of these globs (or /regular expressions/)")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("pattern")
+                .validator(|s| filter::Pattern::parse(&s).map(|_| ()))
+        )
+        .arg(
+            Arg::with_name("exclude-field")
+                .long("exclude-field")
+                .help("Leave out fields and input fields whose `Type.field` path matches one of these globs (or /regular expressions/), like `*.internal*`")
+                .number_of_values(1)
+                .multiple(true)
+                .takes_value(true)
+                .value_name("pattern")
+                .validator(|s| filter::Pattern::parse(&s).map(|_| ()))
+        )
+        .arg(
+            Arg::with_name("hide-connections")
+                .long("hide-connections")
+                .help("Leave Relay connection and edge types out of the index and search")
+        )
+        .arg(
+            Arg::with_name("min-coverage")
+                .long("min-coverage")
+                .help("Fail after writing the documentation if less than this percentage of types, fields, arguments, input fields and enum values have a description")
+                .takes_value(true)
+                .value_name("percent")
+                .validator(|s| match s.parse::<f64>() {
+                    Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(()),
+                    _ => Err("Must be a number between 0 and 100".to_string()),
+                })
+        )
         .group(
             ArgGroup::with_name("source")
-                .args(&["endpoint", "schema"])
+                .args(&["endpoint", "schema", "sdl"])
                 .required(true)
         )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compare two versions of a schema and classify the changes as breaking, dangerous or safe")
+                .arg(
+                    Arg::with_name("old")
+                        .long("old")
+                        .help("The previous version of the schema (introspection JSON or SDL)")
+                        .required(true)
+                        .takes_value(true)
+                        .value_name("schema")
+                )
+                .arg(
+                    Arg::with_name("new")
+                        .long("new")
+                        .help("The next version of the schema (introspection JSON or SDL)")
+                        .required(true)
+                        .takes_value(true)
+                        .value_name("schema")
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("How to print the changes")
+                        .takes_value(true)
+                        .possible_values(&["text", "markdown", "json"])
+                        .default_value("text")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("lint")
+                .about("Check a schema against naming and documentation conventions")
+                .arg(
+                    Arg::with_name("schema")
+                        .long("schema")
+                        .short("s")
+                        .help("The schema to check (introspection JSON or SDL)")
+                        .required(true)
+                        .takes_value(true)
+                        .value_name("path")
+                )
+                .arg(
+                    Arg::with_name("rule")
+                        .long("rule")
+                        .help("Change the level of a rule, like `--rule fields-have-descriptions=error` (levels are error, warning and off)")
+                        .number_of_values(1)
+                        .multiple(true)
+                        .takes_value(true)
+                        .value_name("rule=level")
+                        .validator(|s| {
+                            let (rule, level) = s.split_once('=').ok_or_else(|| "Must be a rule and a level separated by `=`".to_string())?;
+                            if !lint::rule_names().contains(&rule) {
+                                return Err(format!("Unknown rule `{}`, expected one of: {}", rule, lint::rule_names().join(", ")));
+                            }
+                            lint::Level::parse(level).ok_or_else(|| format!("Unknown level `{}`, expected error, warning or off", level))?;
+                            Ok(())
+                        })
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("How to print the findings")
+                        .takes_value(true)
+                        .possible_values(&["human", "json", "sarif"])
+                        .default_value("human")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("graph")
+                .about("Print a graph of how the types in a schema refer to each other")
+                .arg(
+                    Arg::with_name("schema")
+                        .long("schema")
+                        .short("s")
+                        .help("The schema to draw (introspection JSON or SDL)")
+                        .required(true)
+                        .takes_value(true)
+                        .value_name("path")
+                )
+                .arg(
+                    Arg::with_name("type")
+                        .long("type")
+                        .help("Only draw the types around this one instead of the whole schema")
+                        .takes_value(true)
+                        .value_name("name")
+                )
+                .arg(
+                    Arg::with_name("depth")
+                        .long("depth")
+                        .help("How many references away from --type to reach")
+                        .takes_value(true)
+                        .value_name("hops")
+                        .default_value("1")
+                        .validator(|s| s.parse::<usize>().map(|_| ()).map_err(|_| "Must be a whole number".to_string()))
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .help("How to print the graph")
+                        .takes_value(true)
+                        .possible_values(&["dot", "mermaid", "svg"])
+                        .default_value("dot")
+                )
+        )
         .get_matches_from_safe(args)?;
 
+    match matches.subcommand() {
+        ("diff", Some(matches)) => return diff(&runtime, matches).await,
+        ("lint", Some(matches)) => return lint(&runtime, matches).await,
+        ("graph", Some(matches)) => return graph(&runtime, matches).await,
+        _ => {}
+    }
+
     let output = matches.value_of("output").unwrap();
     let name = matches.value_of("name").unwrap();
 
@@ -187,10 +498,20 @@ pub async fn main(runtime: impl Runtime) -> Result<()> {
             }
         }
 
-        Source::Endpoint { url, headers }
+        // This is known to be safe because we validate it in clap's Arg::validator
+        let type_ref_depth = matches
+            .value_of("type-ref-depth")
+            .map_or(DEFAULT_TYPE_REF_DEPTH, |depth| depth.parse().unwrap());
+
+        Source::Endpoint {
+            url,
+            headers,
+            type_ref_depth,
+        }
+    } else if let Some(path) = matches.value_of("sdl") {
+        Source::Sdl { path }
     } else {
-        let path = matches.value_of("schema").unwrap();
-        Source::Schema { path }
+        Source::file(matches.value_of("schema").unwrap())
     };
 
     let date = runtime
@@ -200,55 +521,369 @@ pub async fn main(runtime: impl Runtime) -> Result<()> {
     let date =
         NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| Error::Date(e.to_string()))?;
 
-    let graphql_response = source.get_json(&runtime).await?;
-    let schema = graphql_response.data.schema;
+    let mut schema = source.get_schema(&runtime).await?;
+    schema.check_type_refs()?;
+    validate(&runtime, &schema).await?;
+
+    if let Some(paths) = matches.values_of("save-schema") {
+        for path in paths {
+            save_schema(&runtime, &schema, path).await?;
+        }
+    }
+
+    // These are known to be safe because we validate them in clap's Arg::validator
+    let patterns = |name: &str| -> Vec<filter::Pattern> {
+        matches
+            .values_of(name)
+            .into_iter()
+            .flatten()
+            .map(|pattern| filter::Pattern::parse(pattern).unwrap())
+            .collect()
+    };
+    let filter = filter::Filter {
+        include_types: patterns("include-type"),
+        exclude_types: patterns("exclude-type"),
+        include_fields: patterns("include-field"),
+        exclude_fields: patterns("exclude-field"),
+    };
+    filter.apply(&mut schema);
 
     runtime
-        .prepare_output_directory(&output)
+        .prepare_output_directory(output)
         .await
         .map_err(|e| Error::PrepareOutputDirectory(output.to_string(), e.to_string()))?;
 
-    let renderer = Renderer::new(name.to_string(), date, &schema)?;
+    let history = match matches.value_of("history") {
+        Some(directory) => {
+            let mut snapshots = read_snapshots(&runtime, directory).await?;
+            for snapshot in &mut snapshots {
+                filter.apply(&mut snapshot.schema);
+            }
+            let current = history::Snapshot {
+                label: date.format("%Y-%m-%d").to_string(),
+                schema,
+            };
+            let history = history::History::new(&snapshots, &current);
+            schema = current.schema;
+            Some(history)
+        }
+        None => None,
+    };
+
+    let since = match matches.values_of("since") {
+        Some(values) => {
+            let mut snapshots = Vec::new();
+            for value in values {
+                // This is known to be safe because we validate it in clap's Arg::validator
+                let (label, path) = value.split_once('=').unwrap();
+                let mut schema = Source::file(path).get_schema(&runtime).await?;
+                filter.apply(&mut schema);
+                snapshots.push(history::Snapshot {
+                    label: label.to_string(),
+                    schema,
+                });
+            }
+            Some(history::Since::new(&snapshots))
+        }
+        None => None,
+    };
+
+    let reachability = reachability::Reachability::new(&schema);
+    let exclude_orphans = matches.is_present("exclude-orphans");
+    if exclude_orphans {
+        schema.types.retain(|typ| !reachability.is_orphan(typ));
+    }
+
+    let paths = paths::QueryPaths::new(&schema);
+
+    let renderer = Renderer::new(
+        name.to_string(),
+        date,
+        &schema,
+        history.as_ref(),
+        since.as_ref(),
+        &reachability,
+        &paths,
+    )?
+    // This is known to be safe because we validate it in clap's Arg::validator
+    .with_graph_depth(matches.value_of("graph-depth").unwrap().parse().unwrap())
+    .with_hidden_connections(matches.is_present("hide-connections"));
 
     let index_content = renderer.render_index()?;
     let index_filename = "index.html".to_string();
     runtime
-        .write_file(&output, &index_filename, &index_content)
+        .write_file(output, &index_filename, &index_content)
         .await
         .map_err(|e| Error::WriteFile(index_filename, e.to_string()))?;
     let style_filename = "style.css".to_string();
     runtime
-        .write_file(
-            &output,
-            &style_filename,
-            include_str!("templates/style.css"),
-        )
+        .write_file(output, &style_filename, include_str!("templates/style.css"))
         .await
         .map_err(|e| Error::WriteFile(style_filename, e.to_string()))?;
     let script_filename = "script.js".to_string();
     runtime
         .write_file(
-            &output,
+            output,
             &script_filename,
             include_str!("templates/script.js"),
         )
         .await
         .map_err(|e| Error::WriteFile(script_filename, e.to_string()))?;
 
-    let search_index = search_index::SearchIndex::build(&schema);
+    let sdl_filename = "schema.graphql".to_string();
+    runtime
+        .write_file(output, &sdl_filename, &sdl::print_schema(&schema))
+        .await
+        .map_err(|e| Error::WriteFile(sdl_filename, e.to_string()))?;
+
+    let coverage = coverage::Coverage::new(&schema);
+    let coverage_content = renderer.render_coverage(&coverage)?;
+    let coverage_filename = "coverage.html".to_string();
+    runtime
+        .write_file(output, &coverage_filename, &coverage_content)
+        .await
+        .map_err(|e| Error::WriteFile(coverage_filename, e.to_string()))?;
+
+    let stats = stats::Stats::new(&schema);
+    let stats_content = renderer.render_stats(&stats)?;
+    let stats_filename = "stats.html".to_string();
+    runtime
+        .write_file(output, &stats_filename, &stats_content)
+        .await
+        .map_err(|e| Error::WriteFile(stats_filename, e.to_string()))?;
+    if matches.value_of("stats") == Some("json") {
+        let stats_json_filename = "stats.json".to_string();
+        runtime
+            .write_file(
+                output,
+                &stats_json_filename,
+                &serde_json::to_string_pretty(&stats)?,
+            )
+            .await
+            .map_err(|e| Error::WriteFile(stats_json_filename, e.to_string()))?;
+    }
+
+    let relationships = graph::Relationships::new(&schema);
+    for format in matches.values_of("graph").into_iter().flatten() {
+        let (graph_filename, graph_content) = match format {
+            "mermaid" => ("schema.mmd".to_string(), relationships.whole().to_mermaid()),
+            _ => ("schema.dot".to_string(), relationships.whole().to_dot()),
+        };
+        runtime
+            .write_file(output, &graph_filename, &graph_content)
+            .await
+            .map_err(|e| Error::WriteFile(graph_filename, e.to_string()))?;
+    }
+
+    let orphans_content = renderer.render_orphans(!exclude_orphans)?;
+    let orphans_filename = "orphans.html".to_string();
+    runtime
+        .write_file(output, &orphans_filename, &orphans_content)
+        .await
+        .map_err(|e| Error::WriteFile(orphans_filename, e.to_string()))?;
+
+    if let Some(ref history) = history {
+        let changelog_content = renderer.render_changelog(history)?;
+        let changelog_filename = "changelog.html".to_string();
+        runtime
+            .write_file(output, &changelog_filename, &changelog_content)
+            .await
+            .map_err(|e| Error::WriteFile(changelog_filename, e.to_string()))?;
+    }
+
+    let search_index = search_index::SearchIndex::build(&schema, |typ| renderer.is_listed(typ));
     let search_index = serde_json::to_string_pretty(&search_index)?;
     let search_index_filename = "search-index.json".to_string();
     runtime
-        .write_file(&output, &search_index_filename, &search_index)
+        .write_file(output, &search_index_filename, &search_index)
         .await
         .map_err(|e| Error::WriteFile(search_index_filename, e.to_string()))?;
 
     futures::stream::iter(&schema.types)
-        .map(|t| write_type(&runtime, &output, &renderer, t))
+        .map(|t| write_type(&runtime, output, &renderer, t))
+        .buffered(10)
+        .try_collect::<()>()
+        .await?;
+
+    futures::stream::iter(&schema.directives)
+        .map(|d| write_directive(&runtime, output, &renderer, d))
         .buffered(10)
-        .try_collect()
+        .try_collect::<()>()
         .await?;
 
+    if let Some(min_coverage) = matches.value_of("min-coverage") {
+        // This is known to be safe because we validate it in clap's Arg::validator
+        let min_coverage: f64 = min_coverage.parse().unwrap();
+        if coverage.percent() < min_coverage {
+            return Err(Error::InsufficientCoverage(
+                coverage.percent(),
+                min_coverage,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare two schema files and print the changes between them.
+///
+/// Fails with `Error::BreakingChanges` after printing the changes if any of them are breaking.
+async fn diff(runtime: &impl Runtime, matches: &ArgMatches<'_>) -> Result<()> {
+    // These are known to be safe because clap requires them or gives them a default
+    let old = Source::file(matches.value_of("old").unwrap())
+        .get_schema(runtime)
+        .await?;
+    let new = Source::file(matches.value_of("new").unwrap())
+        .get_schema(runtime)
+        .await?;
+
+    let diff = diff::Diff::new(&old, &new);
+    let report = match matches.value_of("format").unwrap() {
+        "markdown" => diff.to_markdown(),
+        "json" => serde_json::to_string_pretty(&diff)? + "\n",
+        _ => diff.to_text(),
+    };
+    runtime.print(&report).await;
+
+    match diff.count(diff::Severity::Breaking) {
+        0 => Ok(()),
+        breaking => Err(Error::BreakingChanges(breaking)),
+    }
+}
+
+/// Check a schema file against the lint rules and print the findings.
+///
+/// Fails with `Error::LintFailed` after printing the findings if any of them are errors.
+async fn lint(runtime: &impl Runtime, matches: &ArgMatches<'_>) -> Result<()> {
+    // This is known to be safe because clap requires it
+    let path = matches.value_of("schema").unwrap();
+    // An SDL file also tells where each finding is, for the SARIF output
+    let (schema, positions) = match Source::file(path) {
+        Source::Sdl { path } => {
+            let sdl = Source::read_sdl(path, runtime).await?;
+            (sdl::parse_schema(&sdl)?, sdl::positions(&sdl)?)
+        }
+        source => (source.get_schema(runtime).await?, HashMap::new()),
+    };
+
+    let mut levels = HashMap::new();
+    for rule in matches.values_of("rule").into_iter().flatten() {
+        // This is known to be safe because we validate it in clap's Arg::validator
+        let (rule, level) = rule.split_once('=').unwrap();
+        levels.insert(rule.to_string(), lint::Level::parse(level).unwrap());
+    }
+
+    let report = lint::Report::new(&schema, &levels);
+    let output = match matches.value_of("format").unwrap() {
+        "json" => serde_json::to_string_pretty(&report)? + "\n",
+        "sarif" => serde_json::to_string_pretty(&report.to_sarif(path, &positions))? + "\n",
+        _ => report.to_human(),
+    };
+    runtime.print(&output).await;
+
+    match report.count(lint::Level::Error) {
+        0 => Ok(()),
+        errors => Err(Error::LintFailed(errors)),
+    }
+}
+
+/// Print a graph of the whole schema, or of the neighborhood of one type.
+async fn graph(runtime: &impl Runtime, matches: &ArgMatches<'_>) -> Result<()> {
+    // These are known to be safe because clap requires them or gives them a default, and we
+    // validate the depth in clap's Arg::validator
+    let schema = Source::file(matches.value_of("schema").unwrap())
+        .get_schema(runtime)
+        .await?;
+    let depth: usize = matches.value_of("depth").unwrap().parse().unwrap();
+
+    let relationships = graph::Relationships::new(&schema);
+    let graph = match matches.value_of("type") {
+        Some(name) if schema.types.iter().any(|typ| typ.name == name) => {
+            relationships.neighborhood(name, depth)
+        }
+        Some(name) => return Err(Error::UnknownType(name.to_string())),
+        None => relationships.whole(),
+    };
+    let output = match matches.value_of("format").unwrap() {
+        "mermaid" => graph.to_mermaid(),
+        "svg" => graph.to_svg(),
+        _ => graph.to_dot(),
+    };
+    runtime.print(&output).await;
+    Ok(())
+}
+
+/// Read every schema file in the directory whose name starts with a date, oldest first.
+async fn read_snapshots(runtime: &impl Runtime, directory: &str) -> Result<Vec<history::Snapshot>> {
+    let mut files = runtime
+        .read_directory(directory)
+        .await
+        .map_err(|e| Error::ReadDirectory(directory.to_string(), e.to_string()))?;
+    files.sort();
+
+    let mut snapshots = Vec::new();
+    for file in files {
+        let date = match file
+            .get(..10)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        {
+            Some(date) => date,
+            None => {
+                runtime
+                    .log(&format!(
+                        "Skipping {}, its name does not start with a date",
+                        file
+                    ))
+                    .await;
+                continue;
+            }
+        };
+
+        let path = format!("{}/{}", directory, file);
+        let schema = Source::file(&path).get_schema(runtime).await?;
+        snapshots.push(history::Snapshot {
+            label: date.format("%Y-%m-%d").to_string(),
+            schema,
+        });
+    }
+    Ok(snapshots)
+}
+
+/// Log the schema's warnings, and fail if it has errors that would break the documentation.
+async fn validate(runtime: &impl Runtime, schema: &schema::Schema) -> Result<()> {
+    let mut errors = Vec::new();
+    for diagnostic in validation::validate(schema) {
+        match diagnostic.severity {
+            validation::Severity::Error => errors.push(diagnostic.to_string()),
+            validation::Severity::Warning => runtime.log(&format!("Warning: {}", diagnostic)).await,
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidSchema(errors))
+    }
+}
+
+/// Write the schema to a file outside of the output directory, in the format that the file
+/// extension asks for.
+async fn save_schema(runtime: &impl Runtime, schema: &schema::Schema, path: &str) -> Result<()> {
+    let contents = if SDL_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) {
+        sdl::print_schema(schema)
+    } else {
+        schema.to_introspection_json()?
+    };
+
+    let (directory, file) = path.rsplit_once('/').unwrap_or((".", path));
+    runtime
+        .prepare_output_directory(directory)
+        .await
+        .map_err(|e| Error::PrepareOutputDirectory(directory.to_string(), e.to_string()))?;
+    runtime
+        .write_file(directory, file, &contents)
+        .await
+        .map_err(|e| Error::WriteFile(path.to_string(), e.to_string()))?;
     Ok(())
 }
 
@@ -256,27 +891,224 @@ enum Source<'a> {
     Endpoint {
         url: &'a str,
         headers: HashMap<String, String>,
+        /// How deeply the introspection query nests type references
+        type_ref_depth: usize,
     },
     Schema {
         path: &'a str,
     },
+    Sdl {
+        path: &'a str,
+    },
 }
 
-impl Source<'_> {
-    async fn get_json(self, runtime: &impl Runtime) -> Result<schema::GraphQLResponse> {
+impl<'a> Source<'a> {
+    /// A local schema file, read as SDL or as introspection JSON depending on its extension.
+    fn file(path: &'a str) -> Self {
+        if SDL_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) {
+            Source::Sdl { path }
+        } else {
+            Source::Schema { path }
+        }
+    }
+
+    async fn get_schema(self, runtime: &impl Runtime) -> Result<schema::Schema> {
         match self {
-            Self::Endpoint { url, headers } => Self::get_json_endpoint(url, headers, runtime).await,
-            Self::Schema { path } => Self::get_json_schema(path, runtime).await,
+            Self::Endpoint {
+                url,
+                headers,
+                type_ref_depth,
+            } => {
+                let (capabilities, graphql_response) =
+                    Self::get_json_endpoint(url, headers.clone(), type_ref_depth, runtime).await?;
+                let mut schema = Self::schema_from_response(graphql_response, runtime).await?;
+                Self::resolve_truncated_types(
+                    url,
+                    &headers,
+                    &capabilities,
+                    type_ref_depth,
+                    &mut schema,
+                    runtime,
+                )
+                .await;
+                Ok(schema)
+            }
+            Self::Schema { path } => {
+                let graphql_response = Self::get_json_schema(path, runtime).await?;
+                Self::schema_from_response(graphql_response, runtime).await
+            }
+            Self::Sdl { path } => Self::get_sdl(path, runtime).await,
+        }
+    }
+
+    /// Extract the schema from an introspection response.
+    ///
+    /// Errors are only fatal when they come without data; a schema returned alongside partial
+    /// errors is still documented, and the errors are logged.
+    async fn schema_from_response(
+        graphql_response: schema::GraphQLResponse,
+        runtime: &impl Runtime,
+    ) -> Result<schema::Schema> {
+        match graphql_response.data {
+            Some(data) => {
+                for error in &graphql_response.errors {
+                    runtime
+                        .log(&format!(
+                            "The introspection response contained an error: {}",
+                            error
+                        ))
+                        .await;
+                }
+                Ok(data.schema)
+            }
+            None if graphql_response.errors.is_empty() => Err(Error::GraphQL(vec![
+                "The response contained neither data nor errors".to_string(),
+            ])),
+            None => Err(Error::GraphQL(
+                graphql_response
+                    .errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect(),
+            )),
         }
     }
 
+    /// Run the introspection query against the endpoint, returning the capabilities that the
+    /// query that succeeded was built with along with the response.
     async fn get_json_endpoint(
         url: &str,
         headers: HashMap<String, String>,
+        type_ref_depth: usize,
+        runtime: &impl Runtime,
+    ) -> Result<(Capabilities, schema::GraphQLResponse)> {
+        // Find out which optional parts of the introspection schema the server supports, so that
+        // the introspection query asks for as much as possible without being rejected.
+        let capabilities = match runtime
+            .query(url, &Capabilities::probe_request(), headers.clone())
+            .await
+        {
+            Ok(value) => Capabilities::from_probe_response(&value),
+            Err(e) => {
+                runtime
+                    .log(&format!("Capability probe failed: {}", e.to_string()))
+                    .await;
+                None
+            }
+        };
+        let capabilities = match capabilities {
+            Some(capabilities) => {
+                runtime
+                    .log(&format!(
+                        "Detected introspection features: {}",
+                        capabilities.describe()
+                    ))
+                    .await;
+                capabilities
+            }
+            None => {
+                runtime
+                    .log("Could not detect introspection features, using the basic introspection query")
+                    .await;
+                Capabilities::default()
+            }
+        };
+
+        let result = Self::introspect(
+            url,
+            &capabilities.introspection_request(type_ref_depth),
+            headers.clone(),
+            runtime,
+        )
+        .await;
+        // Servers usually reject a query with an unknown field or argument with an HTTP error,
+        // but some answer with errors and no data instead
+        let failure = match &result {
+            Ok(graphql_response) if graphql_response.data.is_none() => {
+                Some("the response contained no data".to_string())
+            }
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        match failure {
+            Some(failure) if capabilities != Capabilities::default() => {
+                runtime
+                    .log(&format!(
+                        "Introspection query failed ({}), retrying with the basic introspection query",
+                        failure
+                    ))
+                    .await;
+                let capabilities = Capabilities::default();
+                let graphql_response = Self::introspect(
+                    url,
+                    &capabilities.introspection_request(type_ref_depth),
+                    headers,
+                    runtime,
+                )
+                .await?;
+                Ok((capabilities, graphql_response))
+            }
+            _ => Ok((capabilities, result?)),
+        }
+    }
+
+    /// Fetch every type whose type references were cut off by the introspection query again on
+    /// its own, doubling the depth of the `TypeRef` fragment until the references fit.
+    ///
+    /// Types that still can't be resolved are left as they are and reported by
+    /// `Schema::check_type_refs`.
+    async fn resolve_truncated_types(
+        url: &str,
+        headers: &HashMap<String, String>,
+        capabilities: &Capabilities,
+        type_ref_depth: usize,
+        schema: &mut schema::Schema,
+        runtime: &impl Runtime,
+    ) {
+        for typ in schema.types.iter_mut() {
+            let mut depth = type_ref_depth;
+            while typ.has_truncated_type_refs() && depth < MAX_TYPE_REF_DEPTH {
+                depth = (depth * 2).min(MAX_TYPE_REF_DEPTH);
+                runtime
+                    .log(&format!(
+                        "Type references in {} are nested too deeply, fetching it again with depth {}",
+                        typ.name, depth
+                    ))
+                    .await;
+
+                let request = capabilities.type_request(&typ.name, depth);
+                let resolved = match runtime.query(url, &request, headers.clone()).await {
+                    Ok(value) => serde_json::from_value::<schema::TypeResponse>(value)
+                        .map_err(|e| e.to_string())
+                        .and_then(|response| {
+                            response
+                                .data
+                                .and_then(|data| data.typ)
+                                .ok_or_else(|| "the type was not returned".to_string())
+                        }),
+                    Err(e) => Err(e.to_string()),
+                };
+                match resolved {
+                    Ok(resolved) => *typ = resolved,
+                    Err(e) => {
+                        runtime
+                            .log(&format!("Failed to fetch {} again: {}", typ.name, e))
+                            .await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn introspect(
+        url: &str,
+        request: &GraphqlRequest,
+        headers: HashMap<String, String>,
         runtime: &impl Runtime,
     ) -> Result<schema::GraphQLResponse> {
         let value = runtime
-            .query(url, &runtime::GRAPHQL_REQUEST, headers)
+            .query(url, request, headers)
             .await
             .map_err(|e| Error::Query(e.to_string()))?;
         let graphql_response: schema::GraphQLResponse = serde_json::from_value(value)?;
@@ -287,13 +1119,36 @@ impl Source<'_> {
         path: &str,
         runtime: &impl Runtime,
     ) -> Result<schema::GraphQLResponse> {
-        let s = runtime
+        let s = if path.ends_with(".gz") {
+            let bytes = runtime
+                .read_binary_file(path)
+                .await
+                .map_err(|e| Error::ReadSchemaFile(e.to_string()))?;
+            let mut s = String::new();
+            flate2::read::GzDecoder::new(bytes.as_slice())
+                .read_to_string(&mut s)
+                .map_err(|e| Error::ReadSchemaFile(e.to_string()))?;
+            s
+        } else {
+            runtime
+                .read_file(path)
+                .await
+                .map_err(|e| Error::ReadSchemaFile(e.to_string()))?
+        };
+
+        let value: serde_json::Value = serde_json::from_str(&s)?;
+        schema::GraphQLResponse::from_json(value)
+    }
+
+    async fn get_sdl(path: &str, runtime: &impl Runtime) -> Result<schema::Schema> {
+        sdl::parse_schema(&Self::read_sdl(path, runtime).await?)
+    }
+
+    async fn read_sdl(path: &str, runtime: &impl Runtime) -> Result<String> {
+        runtime
             .read_file(path)
             .await
-            .map_err(|e| Error::ReadSchemaFile(e.to_string()))?;
-
-        let graphql_response: schema::GraphQLResponse = serde_json::from_str(&s)?;
-        Ok(graphql_response)
+            .map_err(|e| Error::ReadSchemaFile(e.to_string()))
     }
 }
 
@@ -306,12 +1161,12 @@ async fn write_type(
     let file_name = format!("{}.{}.html", full_type.kind.prefix(), full_type.name);
 
     let content = match full_type.kind {
-        schema::Kind::Object => Some(renderer.render_object(&full_type)?),
-        schema::Kind::InputObject => Some(renderer.render_input_object(&full_type)?),
-        schema::Kind::Scalar => Some(renderer.render_scalar(&full_type)?),
-        schema::Kind::Enum => Some(renderer.render_enum(&full_type)?),
-        schema::Kind::Interface => Some(renderer.render_interface(&full_type)?),
-        schema::Kind::Union => Some(renderer.render_union(&full_type)?),
+        schema::Kind::Object => Some(renderer.render_object(full_type)?),
+        schema::Kind::InputObject => Some(renderer.render_input_object(full_type)?),
+        schema::Kind::Scalar => Some(renderer.render_scalar(full_type)?),
+        schema::Kind::Enum => Some(renderer.render_enum(full_type)?),
+        schema::Kind::Interface => Some(renderer.render_interface(full_type)?),
+        schema::Kind::Union => Some(renderer.render_union(full_type)?),
         schema::Kind::List => None,
         schema::Kind::NonNull => None,
     };
@@ -325,3 +1180,234 @@ async fn write_type(
 
     Ok(())
 }
+
+async fn write_directive(
+    runtime: &impl Runtime,
+    output: &str,
+    renderer: &Renderer<'_>,
+    directive: &schema::Directive,
+) -> Result<()> {
+    let file_name = format!("directive.{}.html", directive.name);
+    let content = renderer.render_directive(directive)?;
+
+    runtime
+        .write_file(output, &file_name, &content)
+        .await
+        .map_err(|e| Error::WriteFile(file_name, e.to_string()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_from_response_errors() {
+        let response: schema::GraphQLResponse = serde_json::from_value(json!({
+            "errors": [{ "message": "Introspection is disabled", "path": ["__schema"] }],
+            "data": null,
+        }))
+        .unwrap();
+        let runtime = FakeRuntime::new(Vec::new());
+        let err = futures::executor::block_on(Source::schema_from_response(response, &runtime))
+            .unwrap_err();
+        assert_eq!(err.exit_code(), 14);
+        assert_eq!(
+            err.to_string(),
+            "The GraphQL introspection response contained errors:\n  - Introspection is disabled (path: __schema)"
+        );
+
+        let response: schema::GraphQLResponse = serde_json::from_value(json!({
+            "errors": [{
+                "message": "Could not resolve a description",
+                "path": ["__schema", "types", 3, "description"],
+                "extensions": { "code": "INTERNAL" },
+            }],
+            "data": { "__schema": { "queryType": null, "mutationType": null, "types": [] } },
+        }))
+        .unwrap();
+        assert!(
+            futures::executor::block_on(Source::schema_from_response(response, &runtime)).is_ok()
+        );
+        assert_eq!(
+            *runtime.logs.borrow(),
+            vec![
+                "The introspection response contained an error: Could not resolve a description (path: __schema.types.3.description) (extensions: {\"code\":\"INTERNAL\"})"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_errors_without_data() {
+        // What a runtime returns for an HTTP 400 or 401 whose body is a GraphQL response
+        let rejected = json!({
+            "errors": [{ "message": "Introspection is disabled" }],
+            "data": null,
+        });
+        let runtime = FakeRuntime::new(vec![Ok(rejected.clone()), Ok(rejected)]);
+        let source = Source::Endpoint {
+            url: "http://localhost/graphql",
+            headers: HashMap::new(),
+            type_ref_depth: DEFAULT_TYPE_REF_DEPTH,
+        };
+
+        let err = futures::executor::block_on(source.get_schema(&runtime)).unwrap_err();
+        assert_eq!(err.exit_code(), 14);
+        assert_eq!(
+            err.to_string(),
+            "The GraphQL introspection response contained errors:\n  - Introspection is disabled"
+        );
+    }
+
+    /// A runtime that answers queries from a list of canned responses, in order
+    struct FakeRuntime {
+        responses: std::cell::RefCell<Vec<std::result::Result<serde_json::Value, String>>>,
+        operations: std::cell::RefCell<Vec<String>>,
+        logs: std::cell::RefCell<Vec<String>>,
+        /// The files written, by `output/file` path, which can be read back
+        files: std::cell::RefCell<HashMap<String, String>>,
+    }
+
+    impl FakeRuntime {
+        fn new(responses: Vec<std::result::Result<serde_json::Value, String>>) -> Self {
+            Self {
+                responses: std::cell::RefCell::new(responses),
+                operations: std::cell::RefCell::new(Vec::new()),
+                logs: std::cell::RefCell::new(Vec::new()),
+                files: std::cell::RefCell::new(HashMap::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl Runtime for FakeRuntime {
+        type Error = String;
+
+        async fn date(&self) -> std::result::Result<String, String> {
+            Ok("2021-01-01".to_string())
+        }
+
+        async fn get_args(&self) -> std::result::Result<Vec<String>, String> {
+            Ok(Vec::new())
+        }
+
+        async fn query(
+            &self,
+            _url: &str,
+            graphql: &GraphqlRequest,
+            _headers: HashMap<String, String>,
+        ) -> std::result::Result<serde_json::Value, String> {
+            self.operations
+                .borrow_mut()
+                .push(graphql.operation_name.to_string());
+            self.responses.borrow_mut().remove(0)
+        }
+
+        async fn read_file(&self, path: &str) -> std::result::Result<String, String> {
+            self.files
+                .borrow()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| format!("no file {}", path))
+        }
+
+        async fn read_directory(&self, _path: &str) -> std::result::Result<Vec<String>, String> {
+            Err("no directories".to_string())
+        }
+
+        async fn print(&self, _text: &str) {}
+
+        async fn log(&self, message: &str) {
+            self.logs.borrow_mut().push(message.to_string());
+        }
+
+        async fn prepare_output_directory(&self, _output: &str) -> std::result::Result<(), String> {
+            Ok(())
+        }
+
+        async fn write_file(
+            &self,
+            output: &str,
+            file: &str,
+            contents: &str,
+        ) -> std::result::Result<(), String> {
+            self.files
+                .borrow_mut()
+                .insert(format!("{}/{}", output, file), contents.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_save_schema_round_trip() {
+        let schema = sdl::parse_schema(
+            r#"
+            schema { query: Root subscription: Events }
+            "The root"
+            type Root { user(id: ID!): User @deprecated(reason: "Use `node`") }
+            type Events { userChanged: User }
+            type User { id: ID! role: Role tags(first: Int = 10): [String!] }
+            enum Role { ADMIN GUEST @deprecated }
+            input Filter @oneOf { id: ID name: String }
+            scalar URL @specifiedBy(url: "https://tools.ietf.org/html/rfc3986")
+            directive @auth(role: Role!) repeatable on FIELD_DEFINITION
+            "#,
+        )
+        .unwrap();
+        let runtime = FakeRuntime::new(Vec::new());
+
+        for path in &["saved/schema.json", "saved/schema.graphql"] {
+            futures::executor::block_on(save_schema(&runtime, &schema, path)).unwrap();
+            let saved = futures::executor::block_on(Source::file(path).get_schema(&runtime))
+                .unwrap_or_else(|e| panic!("{}: {}", path, e));
+            assert_eq!(
+                saved.to_introspection_json().unwrap(),
+                schema.to_introspection_json().unwrap(),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_json_endpoint_retries_after_http_error() {
+        let fields = |names: &[&str]| json!({ "fields": names.iter().map(|name| json!({ "name": name, "args": [] })).collect::<Vec<_>>() });
+        let probe = json!({
+            "data": {
+                "schema": fields(&["description", "types"]),
+                "type": fields(&["isOneOf"]),
+                "field": fields(&["args"]),
+                "directive": fields(&["isRepeatable"]),
+                "inputValue": fields(&["name"]),
+            }
+        });
+        let schema = json!({
+            "data": { "__schema": { "queryType": { "name": "Query" }, "types": [] } }
+        });
+        let runtime = FakeRuntime::new(vec![
+            Ok(probe),
+            Err("HTTP status client error (400 Bad Request)".to_string()),
+            Ok(schema),
+        ]);
+
+        let (capabilities, response) = futures::executor::block_on(Source::get_json_endpoint(
+            "http://localhost/graphql",
+            HashMap::new(),
+            DEFAULT_TYPE_REF_DEPTH,
+            &runtime,
+        ))
+        .unwrap();
+        assert_eq!(capabilities, Capabilities::default());
+        assert!(response.data.is_some());
+        assert_eq!(
+            *runtime.operations.borrow(),
+            vec![
+                "IntrospectionCapabilities",
+                "IntrospectionQuery",
+                "IntrospectionQuery"
+            ]
+        );
+    }
+}
@@ -0,0 +1,463 @@
+//! Check a schema against naming and documentation conventions.
+//!
+//! Every rule has a name and a default severity, which can be changed (or the rule turned off)
+//! per run.
+
+use super::schema::{self, Kind, TypeRef};
+use super::sdl;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// How seriously to take the findings of a rule
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Off,
+}
+
+impl Level {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "error" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+/// A single rule violation
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub level: Level,
+    /// The schema coordinate of the offending element, like `User.name(format:)`
+    pub path: String,
+    pub message: String,
+}
+
+struct Rule {
+    name: &'static str,
+    description: &'static str,
+    default_level: Level,
+    check: fn(&schema::Schema, &mut dyn FnMut(String, String)),
+}
+
+static RULES: &[Rule] = &[
+    Rule {
+        name: "type-names-pascal-case",
+        description: "Type names are PascalCase",
+        default_level: Level::Error,
+        check: type_names_pascal_case,
+    },
+    Rule {
+        name: "field-names-camel-case",
+        description: "Field, argument and input field names are camelCase",
+        default_level: Level::Error,
+        check: field_names_camel_case,
+    },
+    Rule {
+        name: "enum-values-screaming-snake-case",
+        description: "Enum values are SCREAMING_SNAKE_CASE",
+        default_level: Level::Error,
+        check: enum_values_screaming_snake_case,
+    },
+    Rule {
+        name: "types-have-descriptions",
+        description: "Every type has a description",
+        default_level: Level::Warning,
+        check: types_have_descriptions,
+    },
+    Rule {
+        name: "fields-have-descriptions",
+        description: "Every field and input field has a description",
+        default_level: Level::Warning,
+        check: fields_have_descriptions,
+    },
+    Rule {
+        name: "deprecations-have-reasons",
+        description: "Every deprecation explains what to use instead",
+        default_level: Level::Warning,
+        check: deprecations_have_reasons,
+    },
+    Rule {
+        name: "no-nullable-list-items",
+        description: "List items are non-null",
+        default_level: Level::Off,
+        check: no_nullable_list_items,
+    },
+];
+
+/// The names of all rules, for validating the command line
+pub fn rule_names() -> Vec<&'static str> {
+    RULES.iter().map(|rule| rule.name).collect()
+}
+
+/// The result of linting a schema
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Run every rule that isn't turned off, using the levels in `levels` instead of the defaults
+    /// where given.
+    pub fn new(schema: &schema::Schema, levels: &HashMap<String, Level>) -> Self {
+        let mut findings = Vec::new();
+        for rule in RULES {
+            let level = levels.get(rule.name).copied().unwrap_or(rule.default_level);
+            if level == Level::Off {
+                continue;
+            }
+            (rule.check)(schema, &mut |path, message| {
+                findings.push(Finding {
+                    rule: rule.name,
+                    level,
+                    path,
+                    message,
+                })
+            });
+        }
+        findings.sort_by(|a, b| (a.level, &a.path, a.rule).cmp(&(b.level, &b.path, b.rule)));
+        Self { findings }
+    }
+
+    pub fn count(&self, level: Level) -> usize {
+        self.findings.iter().filter(|f| f.level == level).count()
+    }
+
+    /// The findings as plain text, one per line.
+    pub fn to_human(&self) -> String {
+        let mut out = String::new();
+        for finding in &self.findings {
+            let level = match finding.level {
+                Level::Error => "error",
+                _ => "warning",
+            };
+            let _ = writeln!(
+                out,
+                "{}[{}]: {}: {}",
+                level, finding.rule, finding.path, finding.message
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{} error(s), {} warning(s)",
+            self.count(Level::Error),
+            self.count(Level::Warning)
+        );
+        out
+    }
+
+    /// The findings as a SARIF 2.1.0 log, for code review tools. `uri` is the schema file that
+    /// was linted, and `positions` where in it each schema coordinate is defined, if known.
+    pub fn to_sarif(&self, uri: &str, positions: &HashMap<String, sdl::Pos>) -> serde_json::Value {
+        let rules: Vec<serde_json::Value> = RULES
+            .iter()
+            .map(|rule| {
+                serde_json::json!({
+                    "id": rule.name,
+                    "shortDescription": { "text": rule.description },
+                })
+            })
+            .collect();
+        let results: Vec<serde_json::Value> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut physical_location =
+                    serde_json::json!({ "artifactLocation": { "uri": uri } });
+                if let Some(pos) = positions.get(&finding.path) {
+                    physical_location["region"] = serde_json::json!({
+                        "startLine": pos.line,
+                        "startColumn": pos.column,
+                    });
+                }
+                serde_json::json!({
+                    "ruleId": finding.rule,
+                    "level": match finding.level {
+                        Level::Error => "error",
+                        _ => "warning",
+                    },
+                    "message": { "text": format!("{}: {}", finding.path, finding.message) },
+                    "locations": [{
+                        "physicalLocation": physical_location,
+                        "logicalLocations": [{ "fullyQualifiedName": finding.path }],
+                    }],
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/bryanburgers/docql",
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
+}
+
+/// The types defined by the schema itself, leaving out introspection types and built-in scalars
+fn own_types(schema: &schema::Schema) -> impl Iterator<Item = &schema::FullType> {
+    schema.types.iter().filter(|typ| !typ.is_built_in())
+}
+
+fn type_names_pascal_case(schema: &schema::Schema, report: &mut dyn FnMut(String, String)) {
+    for typ in own_types(schema) {
+        if !is_pascal_case(&typ.name) {
+            report(
+                typ.name.clone(),
+                format!("Type name `{}` is not PascalCase", typ.name),
+            );
+        }
+    }
+}
+
+fn field_names_camel_case(schema: &schema::Schema, report: &mut dyn FnMut(String, String)) {
+    for typ in own_types(schema) {
+        for field in typ.fields.iter().flatten() {
+            let path = format!("{}.{}", typ.name, field.name);
+            if !is_camel_case(&field.name) {
+                report(
+                    path.clone(),
+                    format!("Field name `{}` is not camelCase", field.name),
+                );
+            }
+            for arg in &field.args {
+                if !is_camel_case(&arg.name) {
+                    report(
+                        format!("{}({}:)", path, arg.name),
+                        format!("Argument name `{}` is not camelCase", arg.name),
+                    );
+                }
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            if !is_camel_case(&input_field.name) {
+                report(
+                    format!("{}.{}", typ.name, input_field.name),
+                    format!("Input field name `{}` is not camelCase", input_field.name),
+                );
+            }
+        }
+    }
+}
+
+fn enum_values_screaming_snake_case(
+    schema: &schema::Schema,
+    report: &mut dyn FnMut(String, String),
+) {
+    for typ in own_types(schema) {
+        for enum_value in typ.enum_values.iter().flatten() {
+            if !is_screaming_snake_case(&enum_value.name) {
+                report(
+                    format!("{}.{}", typ.name, enum_value.name),
+                    format!(
+                        "Enum value `{}` is not SCREAMING_SNAKE_CASE",
+                        enum_value.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn types_have_descriptions(schema: &schema::Schema, report: &mut dyn FnMut(String, String)) {
+    for typ in own_types(schema) {
+        if is_blank(&typ.description) {
+            report(
+                typ.name.clone(),
+                format!("Type `{}` has no description", typ.name),
+            );
+        }
+    }
+}
+
+fn fields_have_descriptions(schema: &schema::Schema, report: &mut dyn FnMut(String, String)) {
+    for typ in own_types(schema) {
+        for field in typ.fields.iter().flatten() {
+            if is_blank(&field.description) {
+                let path = format!("{}.{}", typ.name, field.name);
+                report(path.clone(), format!("Field `{}` has no description", path));
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            if is_blank(&input_field.description) {
+                let path = format!("{}.{}", typ.name, input_field.name);
+                report(
+                    path.clone(),
+                    format!("Input field `{}` has no description", path),
+                );
+            }
+        }
+    }
+}
+
+fn deprecations_have_reasons(schema: &schema::Schema, report: &mut dyn FnMut(String, String)) {
+    let default_reason = |reason: &Option<String>| {
+        reason
+            .as_deref()
+            .is_none_or(|reason| reason.trim().is_empty() || reason == "No longer supported")
+    };
+
+    for typ in own_types(schema) {
+        for field in typ.fields.iter().flatten() {
+            if field.is_deprecated && default_reason(&field.deprecation_reason) {
+                let path = format!("{}.{}", typ.name, field.name);
+                report(
+                    path.clone(),
+                    format!("Deprecated field `{}` does not give a reason", path),
+                );
+            }
+        }
+        for enum_value in typ.enum_values.iter().flatten() {
+            if enum_value.is_deprecated && default_reason(&enum_value.deprecation_reason) {
+                let path = format!("{}.{}", typ.name, enum_value.name);
+                report(
+                    path.clone(),
+                    format!("Deprecated enum value `{}` does not give a reason", path),
+                );
+            }
+        }
+    }
+}
+
+fn no_nullable_list_items(schema: &schema::Schema, report: &mut dyn FnMut(String, String)) {
+    for typ in own_types(schema) {
+        let mut type_refs = Vec::new();
+        for field in typ.fields.iter().flatten() {
+            let path = format!("{}.{}", typ.name, field.name);
+            type_refs.push((path.clone(), &field.typ));
+            for arg in &field.args {
+                type_refs.push((format!("{}({}:)", path, arg.name), &arg.typ));
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            type_refs.push((
+                format!("{}.{}", typ.name, input_field.name),
+                &input_field.typ,
+            ));
+        }
+
+        for (path, type_ref) in type_refs {
+            if has_nullable_list_item(type_ref) {
+                report(
+                    path.clone(),
+                    format!(
+                        "`{}` has type `{}`, whose list items can be null",
+                        path, type_ref
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn has_nullable_list_item(type_ref: &TypeRef) -> bool {
+    match (type_ref.kind, type_ref.of_type.as_deref()) {
+        (Kind::List, Some(of_type)) => {
+            of_type.kind != Kind::NonNull || has_nullable_list_item(of_type)
+        }
+        (_, Some(of_type)) => has_nullable_list_item(of_type),
+        _ => false,
+    }
+}
+
+fn is_blank(description: &Option<String>) -> bool {
+    description.as_deref().is_none_or(|d| d.trim().is_empty())
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && !name.contains('_')
+}
+
+fn is_camel_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase()) && !name.contains('_')
+}
+
+fn is_screaming_snake_case(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_lint() {
+        let source = r#"
+            "The root"
+            type Query {
+              "Look up users"
+              users(first_n: Int): [user]
+            }
+            "A user"
+            type user {
+              "The name"
+              Name: String
+            }
+            "A role"
+            enum Role { admin @deprecated GUEST }
+            "#;
+        let schema = parse_schema(source).unwrap();
+
+        let mut levels = HashMap::new();
+        levels.insert("no-nullable-list-items".to_string(), Level::Warning);
+        levels.insert("type-names-pascal-case".to_string(), Level::Off);
+        let report = Report::new(&schema, &levels);
+
+        let findings: Vec<(Level, &str, &str)> = report
+            .findings
+            .iter()
+            .map(|f| (f.level, f.rule, f.path.as_str()))
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                (
+                    Level::Error,
+                    "field-names-camel-case",
+                    "Query.users(first_n:)"
+                ),
+                (
+                    Level::Error,
+                    "enum-values-screaming-snake-case",
+                    "Role.admin"
+                ),
+                (Level::Error, "field-names-camel-case", "user.Name"),
+                (Level::Warning, "no-nullable-list-items", "Query.users"),
+                (Level::Warning, "deprecations-have-reasons", "Role.admin"),
+            ]
+        );
+
+        let sarif = report.to_sarif("schema.graphql", &crate::sdl::positions(source).unwrap());
+        let regions: Vec<&serde_json::Value> = sarif["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| &result["locations"][0]["physicalLocation"]["region"])
+            .collect();
+        assert_eq!(
+            regions[..3],
+            [
+                &serde_json::json!({ "startLine": 5, "startColumn": 21 }),
+                &serde_json::json!({ "startLine": 13, "startColumn": 25 }),
+                &serde_json::json!({ "startLine": 10, "startColumn": 15 }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! The shortest ways to get hold of a type from the root operation types.
+//!
+//! Readers looking at a type usually want to know which query to write to get one. Starting from
+//! the query and mutation roots, the schema is searched breadth first through output fields, and
+//! through the possible types of interfaces and unions with inline fragments, keeping the first few
+//! paths that arrive at each type.
+
+use super::schema::{self, Kind};
+use std::collections::{HashMap, VecDeque};
+
+/// How many paths are kept for each type
+const PATHS_PER_TYPE: usize = 3;
+
+/// The shortest paths to every type that can be returned by a query or mutation
+#[derive(Debug)]
+pub struct QueryPaths {
+    paths: HashMap<String, Vec<Path>>,
+}
+
+/// A way to reach a type from a root operation type
+#[derive(Debug, Clone)]
+struct Path {
+    operation: &'static str,
+    steps: Vec<Step>,
+    /// The types along the path, including the root, so that it never goes in circles
+    types: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    /// Select a field, giving it the arguments it requires
+    Field {
+        name: String,
+        required_args: Vec<String>,
+    },
+    /// Narrow an interface or union down to one of its possible types
+    Fragment { type_name: String },
+}
+
+impl QueryPaths {
+    pub fn new(schema: &schema::Schema) -> Self {
+        let types: HashMap<&str, &schema::FullType> = schema
+            .types
+            .iter()
+            .map(|typ| (typ.name.as_str(), typ))
+            .collect();
+        let roots = [
+            ("query", &schema.query_type),
+            ("mutation", &schema.mutation_type),
+        ];
+
+        let mut queue = VecDeque::new();
+        for (operation, root) in &roots {
+            if let Some(root) = root {
+                queue.push_back(Path {
+                    operation,
+                    steps: Vec::new(),
+                    types: vec![root.name.clone()],
+                });
+            }
+        }
+
+        let mut paths: HashMap<String, Vec<Path>> = HashMap::new();
+        let is_root = |name: &str| {
+            roots
+                .iter()
+                .any(|(_, root)| root.as_ref().is_some_and(|r| r.name == name))
+        };
+        while let Some(path) = queue.pop_front() {
+            // The last type is always set: it starts out as the root
+            let typ = match types.get(path.types.last().unwrap().as_str()) {
+                Some(typ) => typ,
+                None => continue,
+            };
+
+            let mut next = Vec::new();
+            for field in typ.fields.iter().flatten() {
+                if let Some(name) = field.typ.named_type().name.as_deref() {
+                    next.push((
+                        name,
+                        Step::Field {
+                            name: field.name.clone(),
+                            required_args: field
+                                .args
+                                .iter()
+                                .filter(|arg| {
+                                    arg.typ.kind == Kind::NonNull && arg.default_value.is_none()
+                                })
+                                .map(|arg| format!("{}: {}", arg.name, arg.typ))
+                                .collect(),
+                        },
+                    ));
+                }
+            }
+            for possible_type in typ.possible_types.iter().flatten() {
+                if let Some(ref name) = possible_type.name {
+                    next.push((
+                        name.as_str(),
+                        Step::Fragment {
+                            type_name: name.clone(),
+                        },
+                    ));
+                }
+            }
+
+            for (name, step) in next {
+                if is_root(name) || path.types.iter().any(|t| t == name) {
+                    continue;
+                }
+                let found = paths.entry(name.to_string()).or_default();
+                if found.len() >= PATHS_PER_TYPE {
+                    continue;
+                }
+
+                let mut path = path.clone();
+                path.steps.push(step);
+                path.types.push(name.to_string());
+                found.push(path.clone());
+                queue.push_back(path);
+            }
+        }
+
+        Self { paths }
+    }
+
+    /// The shortest paths to the type, shortest first, written out as operations like
+    /// `query { viewer { organization(login: String!) { team } } }`
+    pub fn for_type(&self, name: &str) -> Vec<String> {
+        self.paths
+            .get(name)
+            .map(|paths| paths.iter().map(Path::to_operation).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Path {
+    fn to_operation(&self) -> String {
+        let mut out = self.operation.to_string();
+        for step in &self.steps {
+            out.push_str(" { ");
+            match step {
+                Step::Field {
+                    name,
+                    required_args,
+                } => {
+                    out.push_str(name);
+                    if !required_args.is_empty() {
+                        out.push_str(&format!("({})", required_args.join(", ")));
+                    }
+                }
+                Step::Fragment { type_name } => out.push_str(&format!("... on {}", type_name)),
+            }
+        }
+        out.push_str(&" }".repeat(self.steps.len()));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_query_paths() {
+        let schema = parse_schema(
+            r#"
+            type Query {
+              viewer: User
+              organization(login: String!, first: Int! = 10): Organization
+              search(text: String): [Result!]!
+            }
+            type Mutation { createTeam(name: String!): Team }
+            type User { organization: Organization }
+            type Organization { team: Team query: Query }
+            type Team { name: String }
+            union Result = Team | User
+            "#,
+        )
+        .unwrap();
+
+        let paths = QueryPaths::new(&schema);
+        assert_eq!(
+            paths.for_type("Team"),
+            vec![
+                "mutation { createTeam(name: String!) }",
+                "query { organization(login: String!) { team } }",
+                "query { search { ... on Team } }",
+            ]
+        );
+        assert_eq!(
+            paths.for_type("Organization"),
+            vec![
+                "query { organization(login: String!) }",
+                "query { viewer { organization } }",
+                "query { search { ... on User { organization } } }",
+            ]
+        );
+        assert!(paths.for_type("Query").is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Which types can be reached from the root operation types.
+//!
+//! A type is reachable if a query, mutation or subscription can return it or take it as input:
+//! through the fields of a root type, their arguments, input fields, interfaces and possible types,
+//! and so on transitively. Types used by directive arguments are reachable too, since documents
+//! can use them. Everything else is an orphan that no client can ever see.
+
+use super::schema::{self, Kind};
+use std::collections::HashSet;
+
+/// The result of walking the schema from its roots
+#[derive(Debug)]
+pub struct Reachability {
+    reachable: HashSet<String>,
+    /// The unreachable types, sorted by name
+    orphans: Vec<Orphan>,
+}
+
+/// A type that can't be reached from the roots
+#[derive(Debug, PartialEq, Eq)]
+pub struct Orphan {
+    pub name: String,
+    pub kind: Kind,
+}
+
+impl Reachability {
+    pub fn new(schema: &schema::Schema) -> Self {
+        let roots = [
+            &schema.query_type,
+            &schema.mutation_type,
+            &schema.subscription_type,
+        ];
+        let mut pending: Vec<&str> = roots
+            .iter()
+            .filter_map(|root| root.as_ref().map(|root| root.name.as_str()))
+            .collect();
+        pending.extend(
+            schema
+                .directives
+                .iter()
+                .flat_map(|directive| &directive.args)
+                .filter_map(|arg| arg.typ.named_type().name.as_deref()),
+        );
+
+        let mut reachable = HashSet::new();
+        while let Some(name) = pending.pop() {
+            if !reachable.insert(name.to_string()) {
+                continue;
+            }
+            if let Some(typ) = schema.types.iter().find(|t| t.name == name) {
+                pending.extend(
+                    typ.type_refs()
+                        .into_iter()
+                        .filter_map(|(_, type_ref)| type_ref.named_type().name.as_deref()),
+                );
+            }
+        }
+
+        let mut orphans: Vec<Orphan> = schema
+            .types
+            .iter()
+            .filter(|typ| !typ.is_built_in() && !reachable.contains(&typ.name))
+            .map(|typ| Orphan {
+                name: typ.name.clone(),
+                kind: typ.kind,
+            })
+            .collect();
+        orphans.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self { reachable, orphans }
+    }
+
+    /// Whether the type can be reached from the roots. Introspection types and built-in scalars
+    /// always count as reachable.
+    pub fn is_orphan(&self, typ: &schema::FullType) -> bool {
+        !typ.is_built_in() && !self.reachable.contains(&typ.name)
+    }
+
+    pub fn orphans(&self) -> &[Orphan] {
+        &self.orphans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_orphans() {
+        let schema = parse_schema(
+            r#"
+            directive @tag(kind: TagKind) on FIELD
+            type Query { node(filter: Filter): Node search: Result }
+            interface Node { id: ID! }
+            type User implements Node { id: ID! role: Role }
+            input Filter { ids: [ID!]! }
+            union Result = Post
+            type Post { author: User }
+            enum Role { ADMIN }
+            enum TagKind { A }
+            type Legacy { user: User old: OldEnum }
+            enum OldEnum { X }
+            type Mutation { ping: Boolean }
+            "#,
+        )
+        .unwrap();
+
+        let reachability = Reachability::new(&schema);
+        let orphans: Vec<&str> = reachability
+            .orphans()
+            .iter()
+            .map(|o| o.name.as_str())
+            .collect();
+        assert_eq!(orphans, vec!["Legacy", "OldEnum"]);
+
+        let user = schema.types.iter().find(|t| t.name == "User").unwrap();
+        assert!(!reachability.is_orphan(user));
+    }
+}
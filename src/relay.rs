@@ -0,0 +1,103 @@
+//! Recognizing the Relay connection pattern.
+//!
+//! A connection is an object with an `edges` field, a list of edge objects that each have a `node`
+//! and a `cursor`, and a `pageInfo` field. Fields that return a connection are paginated lists of
+//! the node type, and take the standard `first`, `after`, `last` and `before` arguments.
+
+use super::schema::{self, Kind};
+use std::collections::HashMap;
+
+/// The arguments that page through a connection
+pub const PAGINATION_ARGS: &[&str] = &["first", "after", "last", "before"];
+
+/// Every connection type in a schema
+#[derive(Debug)]
+pub struct Connections<'a> {
+    connections: HashMap<&'a str, Connection<'a>>,
+}
+
+#[derive(Debug)]
+pub struct Connection<'a> {
+    pub edge: &'a schema::FullType,
+    pub node: &'a schema::FullType,
+}
+
+impl<'a> Connections<'a> {
+    pub fn new(schema: &'a schema::Schema) -> Self {
+        let types: HashMap<&str, &schema::FullType> = schema
+            .types
+            .iter()
+            .map(|typ| (typ.name.as_str(), typ))
+            .collect();
+        let field_type = |typ: &'a schema::FullType, name: &str| {
+            let field = typ.fields.iter().flatten().find(|f| f.name == name)?;
+            types.get(field.typ.named_type().name.as_deref()?).copied()
+        };
+
+        let connections = schema
+            .types
+            .iter()
+            .filter(|typ| typ.kind == Kind::Object)
+            .filter_map(|typ| {
+                let page_info = field_type(typ, "pageInfo")?;
+                let edge = field_type(typ, "edges")?;
+                let node = field_type(edge, "node")?;
+                field_type(edge, "cursor")?;
+                if page_info.kind != Kind::Object || edge.kind != Kind::Object {
+                    return None;
+                }
+                Some((typ.name.as_str(), Connection { edge, node }))
+            })
+            .collect();
+
+        Self { connections }
+    }
+
+    /// The connection that a field's type refers to, if it is one
+    pub fn get(&self, type_ref: &schema::TypeRef) -> Option<&Connection<'a>> {
+        self.connections.get(type_ref.named_type().name.as_deref()?)
+    }
+
+    /// Whether the type is a connection or edge type, which only exist to support pagination
+    pub fn is_generated(&self, typ: &schema::FullType) -> bool {
+        self.connections
+            .values()
+            .any(|connection| connection.edge.name == typ.name)
+            || self.connections.contains_key(typ.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_connections() {
+        let schema = parse_schema(
+            r#"
+            type Query { users(first: Int after: String): UserConnection! tags: TagList }
+            type UserConnection { edges: [UserEdge] pageInfo: PageInfo! totalCount: Int }
+            type UserEdge { node: User cursor: String! }
+            type User { name: String }
+            type PageInfo { hasNextPage: Boolean! endCursor: String }
+            type TagList { edges: [TagEdge] }
+            type TagEdge { node: String cursor: String }
+            "#,
+        )
+        .unwrap();
+        let connections = Connections::new(&schema);
+        let find = |name: &str| schema.types.iter().find(|t| t.name == name).unwrap();
+        let query = find("Query").fields.as_ref().unwrap();
+
+        let users = connections.get(&query[0].typ).unwrap();
+        assert_eq!(users.node.name, "User");
+        assert_eq!(users.edge.name, "UserEdge");
+        assert!(connections.get(&query[1].typ).is_none());
+
+        assert!(connections.is_generated(find("UserConnection")));
+        assert!(connections.is_generated(find("UserEdge")));
+        assert!(!connections.is_generated(find("PageInfo")));
+        assert!(!connections.is_generated(find("TagEdge")));
+    }
+}
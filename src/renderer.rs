@@ -1,4 +1,7 @@
-use super::{handlebars_helpers, schema, Result};
+use super::{
+    coverage, diff, graph, handlebars_helpers, history, paths, reachability, relay, schema, sdl,
+    stats, Result,
+};
 use chrono::NaiveDate;
 use serde::Serialize;
 
@@ -6,11 +9,29 @@ pub struct Renderer<'a> {
     schema_name: String,
     date: NaiveDate,
     schema: &'a schema::Schema,
+    history: Option<&'a history::History>,
+    reachability: &'a reachability::Reachability,
+    paths: &'a paths::QueryPaths,
+    uses: schema::UseIndex<'a>,
+    connections: relay::Connections<'a>,
+    relationships: graph::Relationships<'a>,
+    /// How many references away from a type its page's graph reaches, or 0 for no graph
+    graph_depth: usize,
+    /// Whether Relay connection and edge types are left out of the index
+    hide_connections: bool,
     handlebars: handlebars::Handlebars<'a>,
 }
 
 impl<'a> Renderer<'a> {
-    pub fn new(schema_name: String, date: NaiveDate, schema: &'a schema::Schema) -> Result<Self> {
+    pub fn new(
+        schema_name: String,
+        date: NaiveDate,
+        schema: &'a schema::Schema,
+        history: Option<&'a history::History>,
+        since: Option<&'a history::Since>,
+        reachability: &'a reachability::Reachability,
+        paths: &'a paths::QueryPaths,
+    ) -> Result<Self> {
         let mut handlebars = handlebars::Handlebars::new();
         handlebars.set_strict_mode(true);
         handlebars
@@ -30,35 +51,105 @@ impl<'a> Renderer<'a> {
             include_str!("templates/interface.handlebars"),
         )?;
         handlebars.register_template_string("union", include_str!("templates/union.handlebars"))?;
+        handlebars.register_template_string(
+            "directive",
+            include_str!("templates/directive.handlebars"),
+        )?;
+        handlebars.register_template_string(
+            "changelog",
+            include_str!("templates/changelog.handlebars"),
+        )?;
+        handlebars
+            .register_template_string("coverage", include_str!("templates/coverage.handlebars"))?;
+        handlebars
+            .register_template_string("orphans", include_str!("templates/orphans.handlebars"))?;
+        handlebars.register_template_string("stats", include_str!("templates/stats.handlebars"))?;
 
         handlebars.register_template_string(
             "fields",
             include_str!("templates/partials/fields.handlebars"),
         )?;
+        handlebars.register_template_string(
+            "implements",
+            include_str!("templates/partials/implements.handlebars"),
+        )?;
         handlebars.register_template_string(
             "possible_types",
             include_str!("templates/partials/possible_types.handlebars"),
         )?;
         handlebars
             .register_template_string("uses", include_str!("templates/partials/uses.handlebars"))?;
+        handlebars.register_template_string(
+            "graph",
+            include_str!("templates/partials/graph.handlebars"),
+        )?;
+        handlebars.register_template_string(
+            "deprecated",
+            include_str!("templates/partials/deprecated.handlebars"),
+        )?;
+        handlebars
+            .register_template_string("sdl", include_str!("templates/partials/sdl.handlebars"))?;
+        handlebars.register_template_string(
+            "history",
+            include_str!("templates/partials/history.handlebars"),
+        )?;
+        handlebars.register_template_string(
+            "paths",
+            include_str!("templates/partials/paths.handlebars"),
+        )?;
+        handlebars.register_template_string(
+            "orphan",
+            include_str!("templates/partials/orphan.handlebars"),
+        )?;
 
         handlebars.register_helper(
             "t",
-            Box::new(handlebars_helpers::TypeRefRenderer::new(&schema)),
+            Box::new(handlebars_helpers::TypeRefRenderer::new(schema)),
         );
         handlebars.register_helper(
-            "docblock",
-            Box::new(handlebars_helpers::Docblock::default()),
+            "since",
+            Box::new(handlebars_helpers::SinceRenderer::new(since)),
         );
-        handlebars.register_helper("kind", Box::new(handlebars_helpers::Kind::default()));
+        handlebars.register_helper(
+            "satisfies",
+            Box::new(handlebars_helpers::SatisfiesRenderer::new(schema)),
+        );
+        handlebars.register_helper("docblock", Box::new(handlebars_helpers::Docblock));
+        handlebars.register_helper("kind", Box::new(handlebars_helpers::Kind));
 
         Ok(Self {
             schema_name,
             date,
             schema,
+            history,
+            reachability,
+            paths,
+            uses: schema::UseIndex::new(schema),
+            connections: relay::Connections::new(schema),
+            relationships: graph::Relationships::new(schema),
+            graph_depth: 1,
+            hide_connections: false,
             handlebars,
         })
     }
+
+    /// Show the types within this many references of each type in a graph on its page, or no
+    /// graph for 0.
+    pub fn with_graph_depth(mut self, graph_depth: usize) -> Self {
+        self.graph_depth = graph_depth;
+        self
+    }
+
+    /// Leave the generated Relay connection and edge types out of the index.
+    pub fn with_hidden_connections(mut self, hide_connections: bool) -> Self {
+        self.hide_connections = hide_connections;
+        self
+    }
+
+    /// Whether the type is listed in the index and the search
+    pub fn is_listed(&self, typ: &schema::FullType) -> bool {
+        !(self.hide_connections && self.connections.is_generated(typ))
+    }
 }
 
 impl Renderer<'_> {
@@ -66,7 +157,21 @@ impl Renderer<'_> {
         self.render(
             "index",
             &self.schema_name,
-            &IndexContext::new(&self.schema_name, self.schema),
+            &IndexContext::new(
+                &self.schema_name,
+                self.schema,
+                self.history.is_some(),
+                self.reachability.orphans().len(),
+                self.schema
+                    .types
+                    .iter()
+                    .filter(|typ| !typ.is_built_in() && self.is_listed(typ))
+                    .map(|typ| TypeLink {
+                        name: &typ.name,
+                        class: typ.kind.prefix(),
+                    })
+                    .collect(),
+            ),
         )
     }
 
@@ -74,7 +179,12 @@ impl Renderer<'_> {
         self.render(
             "object",
             &object.name,
-            &ObjectContext::new(&self.schema_name, object, self.schema.find_uses(object)),
+            &ObjectContext::new(
+                self.type_page(object),
+                object,
+                self.schema.root_operation(object),
+                self.field_views(object),
+            ),
         )
     }
 
@@ -82,11 +192,7 @@ impl Renderer<'_> {
         self.render(
             "input_object",
             &input_object.name,
-            &InputObjectContext::new(
-                &self.schema_name,
-                input_object,
-                self.schema.find_uses(input_object),
-            ),
+            &InputObjectContext::new(self.type_page(input_object), input_object),
         )
     }
 
@@ -94,7 +200,7 @@ impl Renderer<'_> {
         self.render(
             "scalar",
             &scalar.name,
-            &ScalarContext::new(&self.schema_name, scalar, self.schema.find_uses(scalar)),
+            &ScalarContext::new(self.type_page(scalar), scalar),
         )
     }
 
@@ -102,11 +208,7 @@ impl Renderer<'_> {
         self.render(
             "enum",
             &enum_type.name,
-            &EnumContext::new(
-                &self.schema_name,
-                enum_type,
-                self.schema.find_uses(enum_type),
-            ),
+            &EnumContext::new(self.type_page(enum_type), enum_type),
         )
     }
 
@@ -115,9 +217,9 @@ impl Renderer<'_> {
             "interface",
             &interface.name,
             &InterfaceContext::new(
-                &self.schema_name,
+                self.type_page(interface),
                 interface,
-                self.schema.find_uses(interface),
+                self.field_views(interface),
             ),
         )
     }
@@ -126,10 +228,119 @@ impl Renderer<'_> {
         self.render(
             "union",
             &union.name,
-            &UnionContext::new(&self.schema_name, union, self.schema.find_uses(union)),
+            &UnionContext::new(self.type_page(union), union),
+        )
+    }
+
+    pub fn render_directive(&self, directive: &schema::Directive) -> Result<String> {
+        self.render(
+            "directive",
+            &format!("@{}", directive.name),
+            &DirectiveContext::new(
+                &self.schema_name,
+                directive,
+                self.type_history(&format!("@{}", directive.name)),
+            ),
+        )
+    }
+
+    pub fn render_changelog(&self, history: &history::History) -> Result<String> {
+        self.render(
+            "changelog",
+            "Changelog",
+            &ChangelogContext::new(&self.schema_name, self.schema, history),
+        )
+    }
+
+    pub fn render_coverage(&self, coverage: &coverage::Coverage) -> Result<String> {
+        self.render(
+            "coverage",
+            "Coverage",
+            &CoverageContext::new(&self.schema_name, coverage),
         )
     }
 
+    pub fn render_stats(&self, stats: &stats::Stats) -> Result<String> {
+        self.render(
+            "stats",
+            "Statistics",
+            &StatsContext {
+                schema_name: &self.schema_name,
+                stats,
+            },
+        )
+    }
+
+    /// The page listing the unreachable types. `linked` says whether their pages were generated.
+    pub fn render_orphans(&self, linked: bool) -> Result<String> {
+        self.render(
+            "orphans",
+            "Orphans",
+            &OrphansContext::new(&self.schema_name, self.reachability, linked),
+        )
+    }
+
+    fn type_page<'t>(&'t self, typ: &'t schema::FullType) -> TypePage<'t> {
+        TypePage {
+            schema_name: &self.schema_name,
+            sdl: sdl::print_type(typ),
+            history: self.type_history(&typ.name),
+            orphan: self.reachability.is_orphan(typ),
+            paths: self.paths.for_type(&typ.name),
+            uses: self.uses.get(typ),
+            graph: self.type_graph(&typ.name),
+        }
+    }
+
+    fn type_graph(&self, name: &str) -> Option<String> {
+        if self.graph_depth == 0 {
+            return None;
+        }
+        let graph = self.relationships.neighborhood(name, self.graph_depth);
+        if graph.is_empty() {
+            None
+        } else {
+            Some(graph.to_svg())
+        }
+    }
+
+    fn field_views<'t>(&'t self, typ: &'t schema::FullType) -> Vec<FieldView<'t>> {
+        typ.fields
+            .iter()
+            .flatten()
+            .map(|field| {
+                let connection = self.connections.get(&field.typ);
+                let is_pagination_arg = |arg: &schema::InputValue| {
+                    connection.is_some() && relay::PAGINATION_ARGS.contains(&arg.name.as_str())
+                };
+                FieldView {
+                    field,
+                    paginates: connection.map(|connection| TypeLink {
+                        name: &connection.node.name,
+                        class: connection.node.kind.prefix(),
+                    }),
+                    regular_args: field
+                        .args
+                        .iter()
+                        .filter(|arg| !is_pagination_arg(arg))
+                        .collect(),
+                    pagination_args: field
+                        .args
+                        .iter()
+                        .filter(|arg| is_pagination_arg(arg))
+                        .map(|arg| arg.name.as_str())
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn type_history(&self, name: &str) -> Vec<history::TypeEntry<'_>> {
+        self.history
+            .map(|history| history.for_type(name))
+            .unwrap_or_default()
+    }
+
     #[inline]
     fn render<T>(&self, template: &str, title: &str, t: &T) -> Result<String>
     where
@@ -139,7 +350,7 @@ impl Renderer<'_> {
         let html = self.handlebars.render(
             "layout",
             &LayoutContext {
-                title: title,
+                title,
                 content: &rendered,
                 date_iso: self.date.format("%Y-%m-%d").to_string(),
                 date_human: self.date.format("%-e %b %Y").to_string(),
@@ -162,38 +373,76 @@ struct LayoutContext<'a> {
 #[serde(rename_all = "camelCase")]
 struct IndexContext<'a> {
     schema_name: &'a str,
+    description: Option<&'a str>,
     query_type: Option<&'a str>,
     mutation_type: Option<&'a str>,
+    subscription_type: Option<&'a str>,
+    directives: Vec<&'a str>,
+    has_changelog: bool,
+    orphan_count: usize,
+    types: Vec<TypeLink<'a>>,
 }
 
 impl<'a> IndexContext<'a> {
-    fn new(schema_name: &'a str, schema: &'a schema::Schema) -> Self {
+    fn new(
+        schema_name: &'a str,
+        schema: &'a schema::Schema,
+        has_changelog: bool,
+        orphan_count: usize,
+        types: Vec<TypeLink<'a>>,
+    ) -> Self {
         Self {
             schema_name,
+            description: schema.description.as_deref(),
             query_type: schema.query_type.as_ref().map(|t| t.name.as_str()),
             mutation_type: schema.mutation_type.as_ref().map(|t| t.name.as_str()),
+            subscription_type: schema.subscription_type.as_ref().map(|t| t.name.as_str()),
+            directives: schema.directives.iter().map(|d| d.name.as_str()).collect(),
+            has_changelog,
+            orphan_count,
+            types,
         }
     }
 }
 
+/// What every type's page shows besides the type itself
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ObjectContext<'a> {
+struct TypePage<'a> {
     schema_name: &'a str,
+    sdl: String,
+    history: Vec<history::TypeEntry<'a>>,
+    /// Whether the type can't be reached from the root operation types
+    orphan: bool,
+    /// The shortest operations that return the type
+    paths: Vec<String>,
+    uses: &'a [schema::TypeUse<'a>],
+    /// An SVG of the types around this one
+    graph: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectContext<'a> {
+    #[serde(flatten)]
+    page: TypePage<'a>,
     object: &'a schema::FullType,
-    uses: Vec<schema::TypeUse<'a>>,
+    root: Option<&'static str>,
+    fields: Vec<FieldView<'a>>,
 }
 
 impl<'a> ObjectContext<'a> {
     fn new(
-        schema_name: &'a str,
+        page: TypePage<'a>,
         object: &'a schema::FullType,
-        uses: Vec<schema::TypeUse<'a>>,
+        root: Option<&'static str>,
+        fields: Vec<FieldView<'a>>,
     ) -> Self {
         Self {
-            schema_name,
+            page,
             object,
-            uses,
+            root,
+            fields,
         }
     }
 }
@@ -201,110 +450,376 @@ impl<'a> ObjectContext<'a> {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct InputObjectContext<'a> {
-    schema_name: &'a str,
+    #[serde(flatten)]
+    page: TypePage<'a>,
     input_object: &'a schema::FullType,
-    uses: Vec<schema::TypeUse<'a>>,
 }
 
 impl<'a> InputObjectContext<'a> {
+    fn new(page: TypePage<'a>, input_object: &'a schema::FullType) -> Self {
+        Self { page, input_object }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScalarContext<'a> {
+    #[serde(flatten)]
+    page: TypePage<'a>,
+    scalar: &'a schema::FullType,
+}
+
+impl<'a> ScalarContext<'a> {
+    fn new(page: TypePage<'a>, scalar: &'a schema::FullType) -> Self {
+        Self { page, scalar }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EnumContext<'a> {
+    #[serde(flatten)]
+    page: TypePage<'a>,
+    #[serde(rename = "enum")]
+    enum_type: &'a schema::FullType,
+}
+
+impl<'a> EnumContext<'a> {
+    fn new(page: TypePage<'a>, enum_type: &'a schema::FullType) -> Self {
+        Self { page, enum_type }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InterfaceContext<'a> {
+    #[serde(flatten)]
+    page: TypePage<'a>,
+    interface: &'a schema::FullType,
+    fields: Vec<FieldView<'a>>,
+}
+
+impl<'a> InterfaceContext<'a> {
     fn new(
-        schema_name: &'a str,
-        input_object: &'a schema::FullType,
-        uses: Vec<schema::TypeUse<'a>>,
+        page: TypePage<'a>,
+        interface: &'a schema::FullType,
+        fields: Vec<FieldView<'a>>,
     ) -> Self {
         Self {
-            schema_name,
-            input_object,
-            uses,
+            page,
+            interface,
+            fields,
         }
     }
 }
 
+/// A field of an object or interface, with its pagination arguments set apart when it returns a
+/// connection
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ScalarContext<'a> {
+struct FieldView<'a> {
+    #[serde(flatten)]
+    field: &'a schema::Field,
+    /// The node type of the connection the field returns
+    paginates: Option<TypeLink<'a>>,
+    /// The arguments that aren't pagination arguments
+    regular_args: Vec<&'a schema::InputValue>,
+    pagination_args: Vec<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeLink<'a> {
+    name: &'a str,
+    class: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnionContext<'a> {
+    #[serde(flatten)]
+    page: TypePage<'a>,
+    union: &'a schema::FullType,
+}
+
+impl<'a> UnionContext<'a> {
+    fn new(page: TypePage<'a>, union: &'a schema::FullType) -> Self {
+        Self { page, union }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DirectiveContext<'a> {
     schema_name: &'a str,
-    scalar: &'a schema::FullType,
-    uses: Vec<schema::TypeUse<'a>>,
+    directive: &'a schema::Directive,
+    history: Vec<history::TypeEntry<'a>>,
 }
 
-impl<'a> ScalarContext<'a> {
+impl<'a> DirectiveContext<'a> {
     fn new(
         schema_name: &'a str,
-        scalar: &'a schema::FullType,
-        uses: Vec<schema::TypeUse<'a>>,
+        directive: &'a schema::Directive,
+        history: Vec<history::TypeEntry<'a>>,
     ) -> Self {
         Self {
             schema_name,
-            scalar,
-            uses,
+            directive,
+            history,
         }
     }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct EnumContext<'a> {
+struct ChangelogContext<'a> {
     schema_name: &'a str,
-    #[serde(rename = "enum")]
-    enum_type: &'a schema::FullType,
-    uses: Vec<schema::TypeUse<'a>>,
+    entries: Vec<ChangelogEntry<'a>>,
 }
 
-impl<'a> EnumContext<'a> {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangelogEntry<'a> {
+    label: &'a str,
+    changes: Vec<ChangelogChange<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangelogChange<'a> {
+    #[serde(flatten)]
+    change: &'a diff::Change,
+    type_name: &'a str,
+    /// The class of the link to the changed type, if its page still exists
+    class: Option<&'static str>,
+    href: Option<String>,
+}
+
+impl<'a> ChangelogContext<'a> {
     fn new(
         schema_name: &'a str,
-        enum_type: &'a schema::FullType,
-        uses: Vec<schema::TypeUse<'a>>,
+        schema: &'a schema::Schema,
+        history: &'a history::History,
     ) -> Self {
+        let entries = history
+            .entries()
+            .map(|entry| ChangelogEntry {
+                label: &entry.label,
+                changes: entry
+                    .changes
+                    .iter()
+                    .map(|change| ChangelogChange::new(schema, change))
+                    .collect(),
+            })
+            .collect();
+
         Self {
             schema_name,
-            enum_type,
-            uses,
+            entries,
+        }
+    }
+}
+
+impl<'a> ChangelogChange<'a> {
+    fn new(schema: &'a schema::Schema, change: &'a diff::Change) -> Self {
+        let type_name = change.type_name();
+        let page = match type_name.strip_prefix('@') {
+            Some(directive) => schema
+                .directives
+                .iter()
+                .find(|d| d.name == directive)
+                .map(|d| ("directive", format!("directive.{}.html", d.name))),
+            None => schema.types.iter().find(|t| t.name == type_name).map(|t| {
+                (
+                    t.kind.prefix(),
+                    format!("{}.{}.html", t.kind.prefix(), t.name),
+                )
+            }),
+        };
+
+        Self {
+            change,
+            type_name,
+            class: page.as_ref().map(|(class, _)| *class),
+            href: page.map(|(_, href)| href),
         }
     }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct InterfaceContext<'a> {
+struct CoverageContext<'a> {
     schema_name: &'a str,
-    interface: &'a schema::FullType,
-    uses: Vec<schema::TypeUse<'a>>,
+    documented: usize,
+    total: usize,
+    percent: String,
+    types: Vec<CoverageType<'a>>,
 }
 
-impl<'a> InterfaceContext<'a> {
-    fn new(
-        schema_name: &'a str,
-        interface: &'a schema::FullType,
-        uses: Vec<schema::TypeUse<'a>>,
-    ) -> Self {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoverageType<'a> {
+    name: &'a str,
+    class: &'static str,
+    href: String,
+    documented: usize,
+    total: usize,
+    percent: String,
+    undocumented: Vec<CoverageItem<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoverageItem<'a> {
+    name: &'a str,
+    href: String,
+}
+
+impl<'a> CoverageContext<'a> {
+    fn new(schema_name: &'a str, coverage: &'a coverage::Coverage) -> Self {
+        let types = coverage
+            .types
+            .iter()
+            .map(|t| {
+                let href = format!("{}.{}.html", t.typ.kind.prefix(), t.typ.name);
+                CoverageType {
+                    name: &t.typ.name,
+                    class: t.typ.kind.prefix(),
+                    documented: t.documented,
+                    total: t.total,
+                    percent: format!("{:.1}", t.percent()),
+                    undocumented: t
+                        .undocumented
+                        .iter()
+                        .map(|item| CoverageItem {
+                            name: &item.name,
+                            href: match item.anchor {
+                                Some(ref anchor) => format!("{}#{}", href, anchor),
+                                None => href.clone(),
+                            },
+                        })
+                        .collect(),
+                    href,
+                }
+            })
+            .collect();
+
         Self {
             schema_name,
-            interface,
-            uses,
+            documented: coverage.documented(),
+            total: coverage.total(),
+            percent: format!("{:.1}", coverage.percent()),
+            types,
         }
     }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct UnionContext<'a> {
+struct OrphansContext<'a> {
     schema_name: &'a str,
-    union: &'a schema::FullType,
-    uses: Vec<schema::TypeUse<'a>>,
+    orphans: Vec<OrphanLink<'a>>,
 }
 
-impl<'a> UnionContext<'a> {
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrphanLink<'a> {
+    name: &'a str,
+    class: &'static str,
+    /// The type's page, unless orphans were left out of the documentation
+    href: Option<String>,
+}
+
+impl<'a> OrphansContext<'a> {
     fn new(
         schema_name: &'a str,
-        union: &'a schema::FullType,
-        uses: Vec<schema::TypeUse<'a>>,
+        reachability: &'a reachability::Reachability,
+        linked: bool,
     ) -> Self {
+        let orphans = reachability
+            .orphans()
+            .iter()
+            .map(|orphan| OrphanLink {
+                name: &orphan.name,
+                class: orphan.kind.prefix(),
+                href: if linked {
+                    Some(format!("{}.{}.html", orphan.kind.prefix(), orphan.name))
+                } else {
+                    None
+                },
+            })
+            .collect();
+
         Self {
             schema_name,
-            union,
-            uses,
+            orphans,
         }
     }
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsContext<'a> {
+    schema_name: &'a str,
+    #[serde(flatten)]
+    stats: &'a stats::Stats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_directive() {
+        let schema = sdl::parse_schema(
+            r#"
+            type Query { ping: Boolean }
+            enum Role { ADMIN GUEST }
+            "Requires the caller to have a role"
+            directive @auth(
+              "The role the caller needs"
+              role: Role! = ADMIN
+              scopes: [String!]
+            ) repeatable on FIELD_DEFINITION | OBJECT
+            directive @internal on ENUM_VALUE
+            "#,
+        )
+        .unwrap();
+        let reachability = reachability::Reachability::new(&schema);
+        let paths = paths::QueryPaths::new(&schema);
+        let renderer = Renderer::new(
+            "test".to_string(),
+            NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
+            &schema,
+            None,
+            None,
+            &reachability,
+            &paths,
+        )
+        .unwrap();
+        let directive = |name: &str| {
+            let directive = schema.directives.iter().find(|d| d.name == name).unwrap();
+            renderer.render_directive(directive).unwrap()
+        };
+
+        let auth = directive("auth");
+        assert!(auth.contains("Requires the caller to have a role"));
+        assert!(auth.contains(r#"<p class="repeatable">"#));
+        assert!(auth.contains(r#"<section id="location.FIELD_DEFINITION" class="location">"#));
+        assert!(auth.contains(r#"<section id="location.OBJECT" class="location">"#));
+        assert!(auth.contains(concat!(
+            r##"<code><a class="arg" href="#arg.role">role</a>: "##,
+            r#"<a class="enum" href="enum.Role.html">Role</a>! = ADMIN</code>"#,
+        )));
+        assert!(auth.contains("The role the caller needs"));
+        assert!(auth.contains(concat!(
+            r##"<code><a class="arg" href="#arg.scopes">scopes</a>: "##,
+            r#"[<a class="scalar" href="scalar.String.html">String</a>!]</code>"#,
+        )));
+
+        let internal = directive("internal");
+        assert!(!internal.contains(r#"<p class="repeatable">"#));
+        assert!(internal.contains(r#"<section id="location.ENUM_VALUE" class="location">"#));
+        assert!(!internal.contains(r#"id="args""#));
+    }
+}
@@ -1,23 +1,31 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::borrow::Cow;
 use std::collections::HashMap;
 
-/// The GraphQL introspection query that will be run
+/// The basic GraphQL introspection query, which every server can answer.
+///
+/// docql itself asks the server which optional introspection fields it supports first, and runs
+/// a richer query built from the answer.
 pub const INTROSPECTION_QUERY: &str = include_str!("introspection_query.graphql");
-/// The GraphQL introspection request that will be executed
+/// The basic GraphQL introspection request
 pub const GRAPHQL_REQUEST: GraphqlRequest = GraphqlRequest {
-    query: INTROSPECTION_QUERY,
-    operation_name: "IntrospectionQuery",
+    query: Cow::Borrowed(INTROSPECTION_QUERY),
+    operation_name: Cow::Borrowed("IntrospectionQuery"),
 };
 
 /// A structure representing a GraphQL request.
+///
+/// Serializes to the request body that the GraphQL over HTTP specification describes, with the
+/// `query` and `operationName` keys.
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GraphqlRequest {
     /// The GraphQL query that will be executed
-    pub query: &'static str,
+    pub query: Cow<'static, str>,
     /// The operation name within the query to execute
-    pub operation_name: &'static str,
+    pub operation_name: Cow<'static, str>,
 }
 
 /// The trait that all tools that use this library must implement.
@@ -40,8 +48,11 @@ pub trait Runtime {
     /// be stripped from the front. This makes implementing the WASM binary easier.
     async fn get_args(&self) -> Result<Vec<String>, Self::Error>;
 
-    /// Run the given GraphQL request (the introspection query) against the URL, returning the JSON
-    /// response.
+    /// Run the given GraphQL request (one of the introspection queries) against the URL, returning
+    /// the JSON response.
+    ///
+    /// A response with an error status whose body contains GraphQL `errors` should still be
+    /// returned, so that the errors can be shown to the user.
     async fn query(
         &self,
         url: &str,
@@ -54,11 +65,32 @@ pub trait Runtime {
     /// Used when rendering documentation based on an already downloaded schema.
     async fn read_file(&self, path: &str) -> Result<String, Self::Error>;
 
+    /// Read a binary file from the filesystem.
+    ///
+    /// Used when rendering documentation based on a compressed schema. The default implementation
+    /// falls back to `read_file`, which only works for files that are valid UTF-8.
+    async fn read_binary_file(&self, path: &str) -> Result<Vec<u8>, Self::Error> {
+        self.read_file(path).await.map(String::into_bytes)
+    }
+
+    /// List the names of the files in a directory.
+    ///
+    /// Used to find the schema snapshots that a changelog is generated from.
+    async fn read_directory(&self, path: &str) -> Result<Vec<String>, Self::Error>;
+
     /// Prepare the output directory.
     ///
     /// The runtime can use this to create the directory, etc.
     async fn prepare_output_directory(&self, output: &str) -> Result<(), Self::Error>;
 
+    /// Print the output of a command, like a schema diff, for the user.
+    async fn print(&self, text: &str);
+
+    /// Report progress or diagnostic information to the user.
+    ///
+    /// The default implementation discards the message.
+    async fn log(&self, _message: &str) {}
+
     /// Write contents to the given file.
     async fn write_file(&self, output: &str, file: &str, contents: &str)
         -> Result<(), Self::Error>;
@@ -1,8 +1,101 @@
+use crate::{Error, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// How many levels of unrelated wrapper objects to look through for the introspection result
+const MAX_ENVELOPE_DEPTH: usize = 4;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GraphQLResponse {
-    pub data: Data,
+    pub data: Option<Data>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQLError>,
+}
+
+impl GraphQLResponse {
+    /// Interpret a JSON document as an introspection response.
+    ///
+    /// Besides the standard `{"data": {"__schema": ...}}` response, this accepts a bare
+    /// `{"__schema": ...}` object, the `__schema` body on its own, and any of these nested inside
+    /// other objects.
+    pub fn from_json(value: Value) -> Result<Self> {
+        let value = find_response(value, MAX_ENVELOPE_DEPTH).ok_or(Error::UnrecognizedSchema)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+fn find_response(value: Value, depth: usize) -> Option<Value> {
+    let mut object = match value {
+        Value::Object(object) => object,
+        _ => return None,
+    };
+
+    let is_response = match object.get("data") {
+        Some(Value::Object(data)) => data.contains_key("__schema"),
+        Some(Value::Null) => object.contains_key("errors"),
+        _ => false,
+    };
+    if is_response {
+        return Some(Value::Object(object));
+    }
+
+    if let Some(schema) = object.remove("__schema") {
+        return Some(serde_json::json!({ "data": { "__schema": schema } }));
+    }
+
+    if let Some(Value::Array(_)) = object.get("types") {
+        return Some(serde_json::json!({ "data": { "__schema": object } }));
+    }
+
+    if depth == 0 {
+        return None;
+    }
+
+    object
+        .into_iter()
+        .find_map(|(_, value)| find_response(value, depth - 1))
+}
+
+/// The response to a query for a single type
+#[derive(Debug, Deserialize)]
+pub struct TypeResponse {
+    pub data: Option<TypeData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TypeData {
+    #[serde(rename = "__type")]
+    pub typ: Option<FullType>,
+}
+
+/// An entry in the `errors` array of a GraphQL response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphQLError {
+    pub message: String,
+    pub path: Option<Vec<Value>>,
+    pub extensions: Option<Value>,
+}
+
+impl fmt::Display for GraphQLError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(ref path) = self.path {
+            let path: Vec<String> = path
+                .iter()
+                .map(|segment| match segment {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            write!(f, " (path: {})", path.join("."))?;
+        }
+        if let Some(ref extensions) = self.extensions {
+            write!(f, " (extensions: {})", extensions)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,22 +107,75 @@ pub struct Data {
 #[derive(Debug, Serialize, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
 #[serde(rename_all = "camelCase")]
 pub struct Schema {
+    /// Only present when the server supports the `description` introspection field
+    #[serde(default)]
+    pub description: Option<String>,
     pub query_type: Option<RootTypeRef>,
     pub mutation_type: Option<RootTypeRef>,
+    pub subscription_type: Option<RootTypeRef>,
     pub types: Vec<FullType>,
+    #[serde(default)]
+    pub directives: Vec<Directive>,
 }
 
 impl Schema {
-    pub fn _find_type(&self, type_ref: &TypeRef) -> Option<&FullType> {
-        let type_ref_name = type_ref.name.as_ref()?;
+    /// The operation ("query", "mutation" or "subscription") that the type is the root type of, if
+    /// any.
+    pub fn root_operation(&self, full_type: &FullType) -> Option<&'static str> {
+        let roots = [
+            ("query", &self.query_type),
+            ("mutation", &self.mutation_type),
+            ("subscription", &self.subscription_type),
+        ];
+
+        roots
+            .iter()
+            .find(|(_, root)| root.as_ref().map(|r| &r.name) == Some(&full_type.name))
+            .map(|(operation, _)| *operation)
+    }
 
-        for typ in &self.types {
-            if &typ.name == type_ref_name {
-                return Some(typ);
+    /// The schema as a pretty-printed introspection response.
+    ///
+    /// Types and directives are sorted by name so that schemas saved at different times can be
+    /// compared line by line.
+    pub fn to_introspection_json(&self) -> Result<String> {
+        let mut value = serde_json::json!({ "data": { "__schema": self } });
+        let schema = &mut value["data"]["__schema"];
+        for key in &["types", "directives"] {
+            if let Value::Array(items) = &mut schema[*key] {
+                items.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
             }
         }
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+
+    /// Every type reference in the schema, along with a description of where it appears.
+    pub fn type_refs(&self) -> Vec<(String, &TypeRef)> {
+        let mut type_refs: Vec<(String, &TypeRef)> =
+            self.types.iter().flat_map(|typ| typ.type_refs()).collect();
+        for directive in &self.directives {
+            for arg in &directive.args {
+                type_refs.push((format!("@{}({})", directive.name, arg.name), &arg.typ));
+            }
+        }
+        type_refs
+    }
 
-        None
+    /// Make sure every type reference in the schema is complete and well formed.
+    pub fn check_type_refs(&self) -> Result<()> {
+        for (location, type_ref) in self.type_refs() {
+            type_ref
+                .check()
+                .map_err(|message| Error::TypeRef(location, message.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// The type that a named type reference refers to, if the schema has it
+    pub fn find_type(&self, type_ref: &TypeRef) -> Option<&FullType> {
+        let type_ref_name = type_ref.name.as_ref()?;
+
+        self.types.iter().find(|typ| &typ.name == type_ref_name)
     }
 }
 
@@ -47,12 +193,66 @@ pub struct TypeRef {
     pub of_type: Option<Box<TypeRef>>,
 }
 
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.kind, &self.of_type) {
+            (Kind::List, Some(of_type)) => write!(f, "[{}]", of_type),
+            (Kind::NonNull, Some(of_type)) => write!(f, "{}!", of_type),
+            _ => write!(f, "{}", self.name.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+impl TypeRef {
+    /// Whether the reference ends in a list or non-null wrapper without the type it wraps, which
+    /// happens when the introspection query does not nest `ofType` deeply enough.
+    pub fn is_truncated(&self) -> bool {
+        match self.kind {
+            Kind::List | Kind::NonNull => match self.of_type {
+                Some(ref of_type) => of_type.is_truncated(),
+                None => true,
+            },
+            _ => false,
+        }
+    }
+
+    /// The named type inside any list and non-null wrappers
+    pub fn named_type(&self) -> &TypeRef {
+        match self.of_type {
+            Some(ref of_type) => of_type.named_type(),
+            None => self,
+        }
+    }
+
+    /// Describe the first problem with the reference, if there is one.
+    pub fn check(&self) -> std::result::Result<(), &'static str> {
+        match self.kind {
+            Kind::List | Kind::NonNull => match self.of_type {
+                Some(ref of_type) if of_type.kind == Kind::NonNull && self.kind == Kind::NonNull => {
+                    Err("a non-null type wraps another non-null type")
+                }
+                Some(ref of_type) => of_type.check(),
+                None => Err("the type reference is truncated; the introspection query did not nest `ofType` deeply enough"),
+            },
+            _ if self.name.is_none() => Err("a named type has no name"),
+            _ if self.of_type.is_some() => Err("a named type wraps another type"),
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
 #[serde(rename_all = "camelCase")]
 pub struct FullType {
     pub kind: Kind,
     pub name: String,
     pub description: Option<String>,
+    /// Only present for scalars when the server supports the `specifiedByURL` introspection field
+    #[serde(default, rename = "specifiedByURL", alias = "specifiedByUrl")]
+    pub specified_by_url: Option<String>,
+    /// Only present for input objects when the server supports the `isOneOf` introspection field
+    #[serde(default)]
+    pub is_one_of: Option<bool>,
     pub fields: Option<Vec<Field>>,
     pub input_fields: Option<Vec<InputValue>>,
     pub interfaces: Option<Vec<TypeRef>>,
@@ -60,7 +260,50 @@ pub struct FullType {
     pub possible_types: Option<Vec<TypeRef>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+impl FullType {
+    /// Every type reference in the type, along with a description of where it appears.
+    pub fn type_refs(&self) -> Vec<(String, &TypeRef)> {
+        let mut type_refs = Vec::new();
+        for field in self.fields.iter().flatten() {
+            type_refs.push((format!("{}.{}", self.name, field.name), &field.typ));
+            for arg in &field.args {
+                type_refs.push((
+                    format!("{}.{}({})", self.name, field.name, arg.name),
+                    &arg.typ,
+                ));
+            }
+        }
+        for input_field in self.input_fields.iter().flatten() {
+            type_refs.push((
+                format!("{}.{}", self.name, input_field.name),
+                &input_field.typ,
+            ));
+        }
+        for interface in self.interfaces.iter().flatten() {
+            type_refs.push((format!("{} (interfaces)", self.name), interface));
+        }
+        for possible_type in self.possible_types.iter().flatten() {
+            type_refs.push((format!("{} (possible types)", self.name), possible_type));
+        }
+        type_refs
+    }
+
+    /// Whether the type is an introspection type or a built-in scalar, which every schema has and
+    /// the schema's authors didn't write
+    pub fn is_built_in(&self) -> bool {
+        self.name.starts_with("__")
+            || ["String", "Int", "Float", "Boolean", "ID"].contains(&self.name.as_str())
+    }
+
+    /// Whether any of the type's references were cut off by the introspection query
+    pub fn has_truncated_type_refs(&self) -> bool {
+        self.type_refs()
+            .iter()
+            .any(|(_, type_ref)| type_ref.is_truncated())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
     NonNull,
@@ -74,7 +317,7 @@ pub enum Kind {
 }
 
 impl Kind {
-    pub fn prefix(&self) -> &str {
+    pub fn prefix(&self) -> &'static str {
         match self {
             Self::NonNull => "non_null",
             Self::List => "list",
@@ -108,6 +351,11 @@ pub struct InputValue {
     #[serde(rename = "type")]
     pub typ: TypeRef,
     pub default_value: Option<String>,
+    /// Only present when the server supports deprecating arguments and input fields
+    #[serde(default)]
+    pub is_deprecated: bool,
+    #[serde(default)]
+    pub deprecation_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
@@ -119,61 +367,72 @@ pub struct EnumValue {
     pub deprecation_reason: Option<String>,
 }
 
-impl Schema {
-    pub fn find_uses(&self, full_type: &FullType) -> Vec<TypeUse> {
-        let mut uses = Vec::new();
-
-        for typ in &self.types {
-            if let Some(ref fields) = typ.fields {
-                for field in fields {
-                    if self.is_use(full_type, &field.typ) {
-                        uses.push(TypeUse::Field { typ, field: &field });
-                    } else {
-                        for arg in &field.args {
-                            if self.is_use(full_type, &arg.typ) {
-                                uses.push(TypeUse::Field { typ, field });
-                                break;
-                            }
-                        }
-                    }
-                }
+#[derive(Debug, Serialize, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+#[serde(rename_all = "camelCase")]
+pub struct Directive {
+    pub name: String,
+    pub description: Option<String>,
+    pub locations: Vec<String>,
+    pub args: Vec<InputValue>,
+    /// Only present when the server supports the `isRepeatable` introspection field
+    #[serde(default)]
+    pub is_repeatable: Option<bool>,
+}
+
+/// Where each type is used, for every type in the schema at once.
+///
+/// Building it walks the schema a single time, so looking up the uses of every type stays linear
+/// in the size of the schema.
+#[derive(Debug)]
+pub struct UseIndex<'a> {
+    uses: HashMap<&'a str, Vec<TypeUse<'a>>>,
+}
+
+impl<'a> UseIndex<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        let mut uses: HashMap<&'a str, Vec<TypeUse<'a>>> = HashMap::new();
+        let mut add = |type_ref: &'a TypeRef, type_use: TypeUse<'a>| {
+            if let Some(ref name) = type_ref.named_type().name {
+                uses.entry(name).or_default().push(type_use);
             }
-            if let Some(ref input_fields) = typ.input_fields {
-                for input_field in input_fields {
-                    if self.is_use(full_type, &input_field.typ) {
-                        uses.push(TypeUse::InputField {
-                            typ,
-                            input_field: &input_field,
-                        });
+        };
+
+        for typ in &schema.types {
+            for field in typ.fields.iter().flatten() {
+                // A field counts once for each type it uses, however many of its arguments do
+                let mut names = vec![&field.typ];
+                for arg in &field.args {
+                    if !names
+                        .iter()
+                        .any(|t| t.named_type().name == arg.typ.named_type().name)
+                    {
+                        names.push(&arg.typ);
                     }
                 }
-            }
-            if let Some(ref possible_types) = typ.possible_types {
-                for possible_type in possible_types {
-                    if self.is_use(full_type, &possible_type) {
-                        uses.push(TypeUse::PossibleType { typ });
-                    }
+                for type_ref in names {
+                    add(type_ref, TypeUse::Field { typ, field });
                 }
             }
+            for input_field in typ.input_fields.iter().flatten() {
+                add(&input_field.typ, TypeUse::InputField { typ, input_field });
+            }
+            for possible_type in typ.possible_types.iter().flatten() {
+                add(possible_type, TypeUse::PossibleType { typ });
+            }
         }
 
-        uses.sort();
-        uses
-    }
-
-    fn is_use(&self, full_type: &FullType, type_ref: &TypeRef) -> bool {
-        if Some(&full_type.name) == type_ref.name.as_ref() {
-            return true;
+        for type_uses in uses.values_mut() {
+            type_uses.sort();
         }
+        Self { uses }
+    }
 
-        if let Some(ref of_type) = type_ref.of_type {
-            match type_ref.kind {
-                Kind::NonNull | Kind::List => self.is_use(full_type, of_type),
-                _ => false,
-            }
-        } else {
-            false
-        }
+    /// The places the type is used, sorted
+    pub fn get(&self, full_type: &FullType) -> &[TypeUse<'a>] {
+        self.uses
+            .get(full_type.name.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
 }
 
@@ -182,19 +441,171 @@ impl Schema {
 pub enum TypeUse<'a> {
     /// The type is used as an input or an output in a field
     Field {
-        #[serde(rename = "type")]
+        #[serde(rename = "type", serialize_with = "serialize_type_name")]
         typ: &'a FullType,
         field: &'a Field,
     },
     /// The type is used as an input field in another input object
     InputField {
-        #[serde(rename = "type")]
+        #[serde(rename = "type", serialize_with = "serialize_type_name")]
         typ: &'a FullType,
         input_field: &'a InputValue,
     },
     /// The type is used as a possible type on an interface or an enumeration
     PossibleType {
-        #[serde(rename = "type")]
+        #[serde(rename = "type", serialize_with = "serialize_type_name")]
         typ: &'a FullType,
     },
 }
+
+/// Serialize only the name and kind of the type that uses another one, which is all the pages
+/// show. Serializing the whole type made the pages of types used by large types like the query
+/// root expensive to render.
+fn serialize_type_name<S>(typ: &&FullType, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeStruct as _;
+
+    let mut state = serializer.serialize_struct("FullType", 2)?;
+    state.serialize_field("kind", &typ.kind)?;
+    state.serialize_field("name", &typ.name)?;
+    state.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_json_shapes() {
+        let body = json!({ "queryType": { "name": "Query" }, "mutationType": null, "types": [] });
+
+        let shapes = vec![
+            json!({ "data": { "__schema": body.clone() } }),
+            json!({ "__schema": body.clone() }),
+            body.clone(),
+            json!({ "result": { "response": { "data": { "__schema": body.clone() } } } }),
+        ];
+        for shape in shapes {
+            let response = GraphQLResponse::from_json(shape).unwrap();
+            let schema = response.data.unwrap().schema;
+            assert_eq!(schema.query_type.unwrap().name, "Query");
+        }
+
+        let err = GraphQLResponse::from_json(json!({ "schema": "type Query" })).unwrap_err();
+        assert_eq!(err.exit_code(), 33);
+    }
+
+    #[test]
+    fn test_root_operation() {
+        let object = |name: &str| {
+            json!({
+                "kind": "OBJECT", "name": name, "description": null, "fields": [], "inputFields": null,
+                "interfaces": [], "enumValues": null, "possibleTypes": null,
+            })
+        };
+        let response = GraphQLResponse::from_json(json!({ "__schema": {
+            "queryType": { "name": "Query" },
+            "mutationType": null,
+            "subscriptionType": { "name": "Events" },
+            "types": [object("Query"), object("Events"), object("Event")],
+            "directives": [],
+        } }))
+        .unwrap();
+        let schema = response.data.unwrap().schema;
+
+        let roots: Vec<Option<&str>> = schema
+            .types
+            .iter()
+            .map(|typ| schema.root_operation(typ))
+            .collect();
+        assert_eq!(roots, vec![Some("query"), Some("subscription"), None]);
+    }
+
+    #[test]
+    fn test_check_type_refs() {
+        let type_ref = |value: Value| -> TypeRef { serde_json::from_value(value).unwrap() };
+
+        let complete = type_ref(json!({
+            "kind": "NON_NULL", "name": null, "ofType": {
+                "kind": "LIST", "name": null, "ofType": {
+                    "kind": "SCALAR", "name": "String", "ofType": null
+                }
+            }
+        }));
+        assert!(!complete.is_truncated());
+        assert_eq!(complete.check(), Ok(()));
+
+        let truncated = type_ref(json!({
+            "kind": "NON_NULL", "name": null, "ofType": {
+                "kind": "LIST", "name": null, "ofType": null
+            }
+        }));
+        assert!(truncated.is_truncated());
+        assert!(truncated.check().is_err());
+
+        let nameless = type_ref(json!({ "kind": "OBJECT", "name": null, "ofType": null }));
+        assert!(!nameless.is_truncated());
+        assert_eq!(nameless.check(), Err("a named type has no name"));
+    }
+
+    #[test]
+    fn test_deserialize_specified_by_url() {
+        for key in &["specifiedByURL", "specifiedByUrl"] {
+            let full_type: FullType = serde_json::from_value(json!({
+                "kind": "SCALAR",
+                "name": "URL",
+                "description": null,
+                key.to_string(): "https://tools.ietf.org/html/rfc3986",
+                "fields": null,
+                "inputFields": null,
+                "interfaces": null,
+                "enumValues": null,
+                "possibleTypes": null,
+            }))
+            .unwrap();
+            assert_eq!(
+                full_type.specified_by_url.as_deref(),
+                Some("https://tools.ietf.org/html/rfc3986")
+            );
+        }
+    }
+
+    #[test]
+    fn test_use_index() {
+        let schema = crate::sdl::parse_schema(
+            r#"
+            type Query { user(id: ID!, other: ID): User users(filter: Filter): [User!]! }
+            type User { id: ID! }
+            input Filter { ids: [ID!] }
+            union Result = User
+            "#,
+        )
+        .unwrap();
+        let index = UseIndex::new(&schema);
+        let find = |name: &str| schema.types.iter().find(|t| t.name == name).unwrap();
+        let describe = |name: &str| -> Vec<String> {
+            index
+                .get(find(name))
+                .iter()
+                .map(|type_use| match type_use {
+                    TypeUse::Field { typ, field } => format!("{}.{}", typ.name, field.name),
+                    TypeUse::InputField { typ, input_field } => {
+                        format!("{}.{}", typ.name, input_field.name)
+                    }
+                    TypeUse::PossibleType { typ } => typ.name.clone(),
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            describe("User"),
+            vec!["Query.user", "Query.users", "Result"]
+        );
+        assert_eq!(describe("ID"), vec!["Query.user", "User.id", "Filter.ids"]);
+        assert_eq!(describe("Filter"), vec!["Query.users"]);
+        assert!(describe("Query").is_empty());
+    }
+}
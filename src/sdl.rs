@@ -0,0 +1,651 @@
+//! Build a [`schema::Schema`] from a GraphQL schema definition language (SDL) document.
+//!
+//! The result mirrors what an introspection query against a server built from the same SDL would
+//! return, so that the rest of the pipeline does not need to know where the schema came from.
+
+use crate::{schema, Result};
+use parser::{Definition, Directive, TypeDefinition, TypeDefinitionKind, Value};
+use std::collections::HashMap;
+
+mod lexer;
+mod parser;
+mod printer;
+
+pub use lexer::Pos;
+pub use printer::{print_schema, print_type};
+
+const BUILT_IN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+const DIRECTIVE_LOCATIONS: &[&str] = &[
+    "QUERY",
+    "MUTATION",
+    "SUBSCRIPTION",
+    "FIELD",
+    "FRAGMENT_DEFINITION",
+    "FRAGMENT_SPREAD",
+    "INLINE_FRAGMENT",
+    "VARIABLE_DEFINITION",
+    "SCHEMA",
+    "SCALAR",
+    "OBJECT",
+    "FIELD_DEFINITION",
+    "ARGUMENT_DEFINITION",
+    "INTERFACE",
+    "UNION",
+    "ENUM",
+    "ENUM_VALUE",
+    "INPUT_OBJECT",
+    "INPUT_FIELD_DEFINITION",
+];
+/// The directives every GraphQL server provides, added unless the document defines them itself
+const BUILT_IN_DIRECTIVES: &str = r#"
+"Directs the executor to include this field or fragment only when the `if` argument is true."
+directive @include("Included when true." if: Boolean!) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+
+"Directs the executor to skip this field or fragment when the `if` argument is true."
+directive @skip("Skipped when true." if: Boolean!) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+
+"Marks an element of a GraphQL schema as no longer supported."
+directive @deprecated(
+  "Explains why this element was deprecated, usually also including a suggestion for how to access supported similar data. Formatted using the Markdown syntax, as specified by [CommonMark](https://commonmark.org/)."
+  reason: String = "No longer supported"
+) on FIELD_DEFINITION | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION | ENUM_VALUE
+
+"Exposes a URL that specifies the behavior of this scalar."
+directive @specifiedBy("The URL that specifies the behavior of this scalar." url: String!) on SCALAR
+
+"Indicates exactly one field must be supplied and this field must not be `null`."
+directive @oneOf on INPUT_OBJECT
+"#;
+
+/// Parse an SDL document into a schema.
+pub fn parse_schema(source: &str) -> Result<schema::Schema> {
+    let document = parser::parse(source)?;
+
+    let mut schema_definition: Option<parser::SchemaDefinition> = None;
+    let mut type_definitions: Vec<TypeDefinition> = Vec::new();
+    let mut directive_definitions: Vec<parser::DirectiveDefinition> = Vec::new();
+    let mut schema_extensions = Vec::new();
+    let mut type_extensions = Vec::new();
+
+    for definition in document.definitions {
+        match definition {
+            Definition::Schema(definition) => {
+                if schema_definition.is_some() {
+                    return Err(definition
+                        .pos
+                        .error("Must provide only one schema definition"));
+                }
+                schema_definition = Some(definition);
+            }
+            Definition::Type(definition) => {
+                if type_definitions.iter().any(|t| t.name == definition.name) {
+                    return Err(definition.pos.error(format!(
+                        "There can be only one type named `{}`",
+                        definition.name
+                    )));
+                }
+                type_definitions.push(definition);
+            }
+            Definition::Directive(definition) => {
+                if directive_definitions
+                    .iter()
+                    .any(|d| d.name == definition.name)
+                {
+                    return Err(definition.pos.error(format!(
+                        "There can be only one directive named `@{}`",
+                        definition.name
+                    )));
+                }
+                directive_definitions.push(definition);
+            }
+            Definition::SchemaExtension(extension) => schema_extensions.push(extension),
+            Definition::TypeExtension(extension) => type_extensions.push(extension),
+        }
+    }
+
+    for definition in parser::parse(BUILT_IN_DIRECTIVES)?.definitions {
+        if let Definition::Directive(definition) = definition {
+            if !directive_definitions
+                .iter()
+                .any(|d| d.name == definition.name)
+            {
+                directive_definitions.push(definition);
+            }
+        }
+    }
+
+    for extension in type_extensions {
+        let definition = type_definitions
+            .iter_mut()
+            .find(|t| t.name == extension.name)
+            .ok_or_else(|| {
+                extension.pos.error(format!(
+                    "Cannot extend type `{}` because it is not defined",
+                    extension.name
+                ))
+            })?;
+        if definition.kind != extension.kind {
+            return Err(extension.pos.error(format!(
+                "Cannot extend `{}` with `extend {}` because it is defined with `{}`",
+                extension.name,
+                extension.kind.keyword(),
+                definition.kind.keyword()
+            )));
+        }
+        definition.directives.extend(extension.directives);
+        definition.interfaces.extend(extension.interfaces);
+        definition.fields.extend(extension.fields);
+        definition.input_fields.extend(extension.input_fields);
+        definition.enum_values.extend(extension.enum_values);
+        definition.members.extend(extension.members);
+    }
+
+    for definition in &type_definitions {
+        unique(&definition.fields, |f| (&f.name, f.pos), "field")?;
+        unique(
+            &definition.input_fields,
+            |f| (&f.name, f.pos),
+            "input field",
+        )?;
+        unique(&definition.enum_values, |v| (&v.name, v.pos), "enum value")?;
+        for field in &definition.fields {
+            unique(&field.args, |a| (&a.name, a.pos), "argument")?;
+        }
+    }
+    for definition in &directive_definitions {
+        unique(&definition.args, |a| (&a.name, a.pos), "argument")?;
+        for location in &definition.locations {
+            if !DIRECTIVE_LOCATIONS.contains(&location.name.as_str()) {
+                return Err(location
+                    .pos
+                    .error(format!("Unknown directive location `{}`", location.name)));
+            }
+        }
+    }
+
+    let mut description = None;
+    let mut operation_types = Vec::new();
+    if let Some(definition) = schema_definition {
+        description = definition.description;
+        operation_types.extend(definition.operation_types);
+    }
+    for extension in schema_extensions {
+        operation_types.extend(extension.operation_types);
+    }
+
+    let mut builder = Builder {
+        kinds: type_definitions
+            .iter()
+            .map(|t| (t.name.clone(), kind(t.kind)))
+            .collect(),
+        added_scalars: Vec::new(),
+    };
+
+    let mut types = Vec::new();
+    for definition in &type_definitions {
+        types.push(builder.full_type(definition, &type_definitions)?);
+    }
+    let directives = directive_definitions
+        .iter()
+        .map(|definition| builder.directive(definition))
+        .collect::<Result<Vec<_>>>()?;
+    for name in builder.added_scalars.drain(..) {
+        types.push(schema::FullType {
+            kind: schema::Kind::Scalar,
+            name,
+            description: None,
+            specified_by_url: None,
+            is_one_of: None,
+            fields: None,
+            input_fields: None,
+            interfaces: None,
+            enum_values: None,
+            possible_types: None,
+        });
+    }
+
+    let query_type = builder.root_type("query", &operation_types, "Query")?;
+    let mutation_type = builder.root_type("mutation", &operation_types, "Mutation")?;
+    let subscription_type = builder.root_type("subscription", &operation_types, "Subscription")?;
+
+    Ok(schema::Schema {
+        description,
+        query_type,
+        mutation_type,
+        subscription_type,
+        types,
+        directives,
+    })
+}
+
+/// Where each element of an SDL document is defined, by its schema coordinate: `Type`,
+/// `Type.field`, `Type.field(arg:)`, `Enum.VALUE`, `@directive` and `@directive(arg:)`.
+///
+/// Elements added by a type extension point at the extension.
+pub fn positions(source: &str) -> Result<HashMap<String, Pos>> {
+    let mut positions = HashMap::new();
+    for definition in parser::parse(source)?.definitions {
+        match definition {
+            Definition::Type(definition) | Definition::TypeExtension(definition) => {
+                let name = &definition.name;
+                positions.entry(name.clone()).or_insert(definition.pos);
+                for field in &definition.fields {
+                    let path = format!("{}.{}", name, field.name);
+                    for arg in &field.args {
+                        positions.insert(format!("{}({}:)", path, arg.name), arg.pos);
+                    }
+                    positions.insert(path, field.pos);
+                }
+                for input_field in &definition.input_fields {
+                    positions.insert(format!("{}.{}", name, input_field.name), input_field.pos);
+                }
+                for enum_value in &definition.enum_values {
+                    positions.insert(format!("{}.{}", name, enum_value.name), enum_value.pos);
+                }
+            }
+            Definition::Directive(definition) => {
+                let path = format!("@{}", definition.name);
+                for arg in &definition.args {
+                    positions.insert(format!("{}({}:)", path, arg.name), arg.pos);
+                }
+                positions.insert(path, definition.pos);
+            }
+            Definition::Schema(_) | Definition::SchemaExtension(_) => {}
+        }
+    }
+    Ok(positions)
+}
+
+fn kind(kind: TypeDefinitionKind) -> schema::Kind {
+    match kind {
+        TypeDefinitionKind::Scalar => schema::Kind::Scalar,
+        TypeDefinitionKind::Object => schema::Kind::Object,
+        TypeDefinitionKind::Interface => schema::Kind::Interface,
+        TypeDefinitionKind::Union => schema::Kind::Union,
+        TypeDefinitionKind::Enum => schema::Kind::Enum,
+        TypeDefinitionKind::InputObject => schema::Kind::InputObject,
+    }
+}
+
+/// Make sure that no two items share a name.
+fn unique<T>(items: &[T], key: impl Fn(&T) -> (&String, Pos), what: &str) -> Result<()> {
+    for (i, item) in items.iter().enumerate() {
+        let (name, pos) = key(item);
+        if items[..i].iter().any(|other| key(other).0 == name) {
+            return Err(pos.error(format!("There can be only one {} named `{}`", what, name)));
+        }
+    }
+    Ok(())
+}
+
+fn deprecation(directives: &[Directive]) -> (bool, Option<String>) {
+    match directives.iter().find(|d| d.name == "deprecated") {
+        Some(directive) => {
+            let reason = match directive.arg("reason") {
+                Some(Value::String(reason)) => reason.clone(),
+                _ => DEFAULT_DEPRECATION_REASON.to_string(),
+            };
+            (true, Some(reason))
+        }
+        None => (false, None),
+    }
+}
+
+struct Builder {
+    kinds: HashMap<String, schema::Kind>,
+    added_scalars: Vec<String>,
+}
+
+impl Builder {
+    fn root_type(
+        &self,
+        operation: &str,
+        operation_types: &[parser::OperationType],
+        default_name: &str,
+    ) -> Result<Option<schema::RootTypeRef>> {
+        let explicit = operation_types.iter().find(|o| o.operation == operation);
+        let (name, pos) = match explicit {
+            Some(operation_type) => (operation_type.type_name.as_str(), Some(operation_type.pos)),
+            None if operation_types.is_empty() => (default_name, None),
+            None => return Ok(None),
+        };
+
+        match (self.kinds.get(name), pos) {
+            (Some(schema::Kind::Object), _) => Ok(Some(schema::RootTypeRef {
+                name: name.to_string(),
+            })),
+            (_, Some(pos)) => Err(pos.error(format!(
+                "The {} root type `{}` must be a defined object type",
+                operation, name
+            ))),
+            (_, None) => Ok(None),
+        }
+    }
+
+    fn full_type(
+        &mut self,
+        definition: &TypeDefinition,
+        definitions: &[TypeDefinition],
+    ) -> Result<schema::FullType> {
+        let kind = kind(definition.kind);
+
+        let fields = match kind {
+            schema::Kind::Object | schema::Kind::Interface => Some(
+                definition
+                    .fields
+                    .iter()
+                    .map(|field| self.field(field))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            _ => None,
+        };
+
+        let input_fields = match kind {
+            schema::Kind::InputObject => Some(
+                definition
+                    .input_fields
+                    .iter()
+                    .map(|input_field| self.input_value(input_field))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            _ => None,
+        };
+
+        let interfaces = match kind {
+            schema::Kind::Object | schema::Kind::Interface => Some(
+                definition
+                    .interfaces
+                    .iter()
+                    .map(|interface| self.named_type_ref(interface, Some(schema::Kind::Interface)))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            _ => None,
+        };
+
+        let enum_values = match kind {
+            schema::Kind::Enum => Some(
+                definition
+                    .enum_values
+                    .iter()
+                    .map(|enum_value| {
+                        let (is_deprecated, deprecation_reason) =
+                            deprecation(&enum_value.directives);
+                        schema::EnumValue {
+                            name: enum_value.name.clone(),
+                            description: enum_value.description.clone(),
+                            is_deprecated,
+                            deprecation_reason,
+                        }
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        let possible_types = match kind {
+            schema::Kind::Union => Some(
+                definition
+                    .members
+                    .iter()
+                    .map(|member| self.named_type_ref(member, Some(schema::Kind::Object)))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            schema::Kind::Interface => Some(
+                definitions
+                    .iter()
+                    .filter(|d| d.kind == TypeDefinitionKind::Object)
+                    .filter(|d| d.interfaces.iter().any(|i| i.name == definition.name))
+                    .map(|d| schema::TypeRef {
+                        kind: schema::Kind::Object,
+                        name: Some(d.name.clone()),
+                        of_type: None,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        let specified_by_url = match kind {
+            schema::Kind::Scalar => definition
+                .directives
+                .iter()
+                .find(|d| d.name == "specifiedBy")
+                .and_then(|d| match d.arg("url") {
+                    Some(Value::String(url)) => Some(url.clone()),
+                    _ => None,
+                }),
+            _ => None,
+        };
+
+        let is_one_of = match kind {
+            schema::Kind::InputObject => {
+                Some(definition.directives.iter().any(|d| d.name == "oneOf"))
+            }
+            _ => None,
+        };
+
+        Ok(schema::FullType {
+            kind,
+            name: definition.name.clone(),
+            description: definition.description.clone(),
+            specified_by_url,
+            is_one_of,
+            fields,
+            input_fields,
+            interfaces,
+            enum_values,
+            possible_types,
+        })
+    }
+
+    fn directive(&mut self, definition: &parser::DirectiveDefinition) -> Result<schema::Directive> {
+        Ok(schema::Directive {
+            name: definition.name.clone(),
+            description: definition.description.clone(),
+            locations: definition
+                .locations
+                .iter()
+                .map(|location| location.name.clone())
+                .collect(),
+            args: definition
+                .args
+                .iter()
+                .map(|arg| self.input_value(arg))
+                .collect::<Result<Vec<_>>>()?,
+            is_repeatable: Some(definition.is_repeatable),
+        })
+    }
+
+    fn field(&mut self, field: &parser::FieldDefinition) -> Result<schema::Field> {
+        let (is_deprecated, deprecation_reason) = deprecation(&field.directives);
+
+        Ok(schema::Field {
+            name: field.name.clone(),
+            description: field.description.clone(),
+            args: field
+                .args
+                .iter()
+                .map(|arg| self.input_value(arg))
+                .collect::<Result<Vec<_>>>()?,
+            typ: self.type_ref(&field.typ)?,
+            is_deprecated,
+            deprecation_reason,
+        })
+    }
+
+    fn input_value(
+        &mut self,
+        input_value: &parser::InputValueDefinition,
+    ) -> Result<schema::InputValue> {
+        let (is_deprecated, deprecation_reason) = deprecation(&input_value.directives);
+
+        Ok(schema::InputValue {
+            name: input_value.name.clone(),
+            description: input_value.description.clone(),
+            typ: self.type_ref(&input_value.typ)?,
+            default_value: input_value.default_value.as_ref().map(|v| v.to_string()),
+            is_deprecated,
+            deprecation_reason,
+        })
+    }
+
+    fn type_ref(&mut self, typ: &parser::Type) -> Result<schema::TypeRef> {
+        match typ {
+            parser::Type::Named(named) => self.named_type_ref(named, None),
+            parser::Type::List(of_type) => Ok(schema::TypeRef {
+                kind: schema::Kind::List,
+                name: None,
+                of_type: Some(Box::new(self.type_ref(of_type)?)),
+            }),
+            parser::Type::NonNull(of_type) => Ok(schema::TypeRef {
+                kind: schema::Kind::NonNull,
+                name: None,
+                of_type: Some(Box::new(self.type_ref(of_type)?)),
+            }),
+        }
+    }
+
+    /// Look up the kind of a named type, making sure it exists and (optionally) that it is of the
+    /// expected kind.
+    fn named_type_ref(
+        &mut self,
+        named: &parser::Named,
+        expected: Option<schema::Kind>,
+    ) -> Result<schema::TypeRef> {
+        let kind = match self.kinds.get(&named.name) {
+            Some(kind) => *kind,
+            None if BUILT_IN_SCALARS.contains(&named.name.as_str()) => {
+                self.kinds.insert(named.name.clone(), schema::Kind::Scalar);
+                self.added_scalars.push(named.name.clone());
+                schema::Kind::Scalar
+            }
+            None => return Err(named.pos.error(format!("Unknown type `{}`", named.name))),
+        };
+
+        if let Some(expected) = expected {
+            if kind != expected {
+                return Err(named.pos.error(format!(
+                    "Type `{}` must be an {} type",
+                    named.name,
+                    expected.prefix()
+                )));
+            }
+        }
+
+        Ok(schema::TypeRef {
+            kind,
+            name: Some(named.name.clone()),
+            of_type: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_parse_schema() {
+        let schema = parse_schema(
+            r#"
+            """
+            Something with an `id`
+            """
+            interface Node {
+              id: ID!
+            }
+
+            type Query {
+              "Fetch a node"
+              node(id: ID!, first: Int = 10): Node
+              old: String @deprecated
+            }
+
+            type User implements Node {
+              id: ID!
+            }
+
+            extend type Query {
+              users(order: Order = ASC): [User!]!
+            }
+
+            enum Order { ASC DESC @deprecated(reason: "Use ASC") }
+
+            directive @auth(role: String!) repeatable on FIELD_DEFINITION | OBJECT
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(schema.query_type.unwrap().name, "Query");
+        assert!(schema.mutation_type.is_none());
+        assert!(schema.subscription_type.is_none());
+
+        let names: Vec<&str> = schema.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Node", "Query", "User", "Order", "ID", "Int", "String", "Boolean"]
+        );
+
+        let node = &schema.types[0];
+        assert_eq!(node.description.as_deref(), Some("Something with an `id`"));
+        assert_eq!(
+            node.possible_types.as_ref().unwrap()[0].name.as_deref(),
+            Some("User")
+        );
+
+        let query_fields = schema.types[1].fields.as_ref().unwrap();
+        assert_eq!(query_fields.len(), 3);
+        assert_eq!(query_fields[0].args[1].default_value.as_deref(), Some("10"));
+        assert!(query_fields[1].is_deprecated);
+        assert_eq!(
+            query_fields[1].deprecation_reason.as_deref(),
+            Some("No longer supported")
+        );
+        assert_eq!(query_fields[2].typ.kind, schema::Kind::NonNull);
+        assert_eq!(query_fields[2].args[0].typ.kind, schema::Kind::Enum);
+
+        let order_values = schema.types[3].enum_values.as_ref().unwrap();
+        assert_eq!(
+            order_values[1].deprecation_reason.as_deref(),
+            Some("Use ASC")
+        );
+
+        let directives: Vec<&str> = schema.directives.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            directives,
+            vec![
+                "auth",
+                "include",
+                "skip",
+                "deprecated",
+                "specifiedBy",
+                "oneOf"
+            ]
+        );
+        assert_eq!(schema.directives[0].is_repeatable, Some(true));
+        assert_eq!(
+            schema.directives[0].locations,
+            vec!["FIELD_DEFINITION", "OBJECT"]
+        );
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let err = parse_schema("type Query {\n  a: String\n  b: Strin\n}").unwrap_err();
+        match err {
+            Error::SdlParse(line, column, message) => {
+                assert_eq!((line, column), (3, 6));
+                assert_eq!(message, "Unknown type `Strin`");
+            }
+            err => panic!("Unexpected error {:?}", err),
+        }
+
+        let err = parse_schema("type Query {\n  a String\n}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse GraphQL SDL at line 2, column 5: Expected `:`, found `String`"
+        );
+    }
+}
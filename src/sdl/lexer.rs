@@ -0,0 +1,424 @@
+use crate::{Error, Result};
+
+/// A position in the source document. Both the line and the column are 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pos {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Pos {
+    pub fn error(self, message: impl Into<String>) -> Error {
+        Error::SdlParse(self.line, self.column, message.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Bang,
+    Dollar,
+    Amp,
+    ParenOpen,
+    ParenClose,
+    Spread,
+    Colon,
+    Equals,
+    At,
+    BracketOpen,
+    BracketClose,
+    BraceOpen,
+    BraceClose,
+    Pipe,
+    Name(String),
+    Int(String),
+    Float(String),
+    String(String),
+    BlockString(String),
+    Eof,
+}
+
+impl Token {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Bang => "`!`".to_string(),
+            Self::Dollar => "`$`".to_string(),
+            Self::Amp => "`&`".to_string(),
+            Self::ParenOpen => "`(`".to_string(),
+            Self::ParenClose => "`)`".to_string(),
+            Self::Spread => "`...`".to_string(),
+            Self::Colon => "`:`".to_string(),
+            Self::Equals => "`=`".to_string(),
+            Self::At => "`@`".to_string(),
+            Self::BracketOpen => "`[`".to_string(),
+            Self::BracketClose => "`]`".to_string(),
+            Self::BraceOpen => "`{`".to_string(),
+            Self::BraceClose => "`}`".to_string(),
+            Self::Pipe => "`|`".to_string(),
+            Self::Name(name) => format!("`{}`", name),
+            Self::Int(value) | Self::Float(value) => format!("number `{}`", value),
+            Self::String(_) | Self::BlockString(_) => "string".to_string(),
+            Self::Eof => "end of file".to_string(),
+        }
+    }
+}
+
+/// Split a GraphQL document into tokens, skipping whitespace, commas and comments.
+pub fn tokenize(source: &str) -> Result<Vec<(Token, Pos)>> {
+    let mut lexer = Lexer {
+        chars: source.chars().collect(),
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+
+    let mut tokens = Vec::new();
+    loop {
+        lexer.skip_ignored();
+        let pos = lexer.pos();
+        let token = lexer.next_token()?;
+        let done = token == Token::Eof;
+        tokens.push((token, pos));
+        if done {
+            return Ok(tokens);
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer {
+    fn pos(&self) -> Pos {
+        Pos {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.offset).copied()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.offset + n).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += 1;
+        match c {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            '\r' => {
+                if self.peek() != Some('\n') {
+                    self.line += 1;
+                    self.column = 1;
+                }
+            }
+            _ => self.column += 1,
+        }
+        Some(c)
+    }
+
+    fn skip_ignored(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                '\u{feff}' | ' ' | '\t' | '\n' | '\r' | ',' => {
+                    self.bump();
+                }
+                '#' => {
+                    while let Some(c) = self.peek() {
+                        if c == '\n' || c == '\r' {
+                            break;
+                        }
+                        self.bump();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token> {
+        let pos = self.pos();
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Ok(Token::Eof),
+        };
+
+        let simple = match c {
+            '!' => Some(Token::Bang),
+            '$' => Some(Token::Dollar),
+            '&' => Some(Token::Amp),
+            '(' => Some(Token::ParenOpen),
+            ')' => Some(Token::ParenClose),
+            ':' => Some(Token::Colon),
+            '=' => Some(Token::Equals),
+            '@' => Some(Token::At),
+            '[' => Some(Token::BracketOpen),
+            ']' => Some(Token::BracketClose),
+            '{' => Some(Token::BraceOpen),
+            '}' => Some(Token::BraceClose),
+            '|' => Some(Token::Pipe),
+            _ => None,
+        };
+        if let Some(token) = simple {
+            self.bump();
+            return Ok(token);
+        }
+
+        match c {
+            '.' => {
+                if self.peek_at(1) == Some('.') && self.peek_at(2) == Some('.') {
+                    self.bump();
+                    self.bump();
+                    self.bump();
+                    Ok(Token::Spread)
+                } else {
+                    Err(pos.error("Unexpected character `.`"))
+                }
+            }
+            '"' => {
+                if self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') {
+                    self.block_string()
+                } else {
+                    self.string()
+                }
+            }
+            '-' | '0'..='9' => self.number(),
+            c if c == '_' || c.is_ascii_alphabetic() => {
+                let mut name = String::new();
+                while let Some(c) = self.peek() {
+                    if c == '_' || c.is_ascii_alphanumeric() {
+                        name.push(c);
+                        self.bump();
+                    } else {
+                        break;
+                    }
+                }
+                Ok(Token::Name(name))
+            }
+            c => Err(pos.error(format!("Unexpected character {:?}", c))),
+        }
+    }
+
+    fn number(&mut self) -> Result<Token> {
+        let pos = self.pos();
+        let mut value = String::new();
+        let mut is_float = false;
+
+        if self.peek() == Some('-') {
+            value.push('-');
+            self.bump();
+        }
+
+        match self.peek() {
+            Some('0') => {
+                value.push('0');
+                self.bump();
+                if let Some(c) = self.peek() {
+                    if c.is_ascii_digit() {
+                        return Err(self.pos().error("Invalid number, unexpected digit after 0"));
+                    }
+                }
+            }
+            Some(c) if c.is_ascii_digit() => self.digits(&mut value),
+            _ => return Err(pos.error("Invalid number, expected digit")),
+        }
+
+        if self.peek() == Some('.') {
+            is_float = true;
+            value.push('.');
+            self.bump();
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.pos().error("Invalid number, expected digit after `.`"));
+            }
+            self.digits(&mut value);
+        }
+
+        if let Some(e @ 'e') | Some(e @ 'E') = self.peek() {
+            is_float = true;
+            value.push(e);
+            self.bump();
+            if let Some(sign @ '+') | Some(sign @ '-') = self.peek() {
+                value.push(sign);
+                self.bump();
+            }
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self
+                    .pos()
+                    .error("Invalid number, expected digit in exponent"));
+            }
+            self.digits(&mut value);
+        }
+
+        if let Some(c) = self.peek() {
+            if c == '.' || c == '_' || c.is_ascii_alphabetic() {
+                return Err(self
+                    .pos()
+                    .error(format!("Invalid number, unexpected character {:?}", c)));
+            }
+        }
+
+        if is_float {
+            Ok(Token::Float(value))
+        } else {
+            Ok(Token::Int(value))
+        }
+    }
+
+    fn digits(&mut self, value: &mut String) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() {
+                value.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<Token> {
+        let start = self.pos();
+        self.bump();
+
+        let mut value = String::new();
+        loop {
+            let pos = self.pos();
+            match self.bump() {
+                None | Some('\n') | Some('\r') => return Err(start.error("Unterminated string")),
+                Some('"') => return Ok(Token::String(value)),
+                Some('\\') => match self.bump() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('u') => value.push(self.unicode_escape(pos)?),
+                    _ => return Err(pos.error("Invalid escape sequence")),
+                },
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self, pos: Pos) -> Result<char> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .bump()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| pos.error("Invalid unicode escape sequence"))?;
+            code = code * 16 + digit;
+        }
+        std::char::from_u32(code).ok_or_else(|| pos.error("Invalid unicode escape sequence"))
+    }
+
+    fn block_string(&mut self) -> Result<Token> {
+        let start = self.pos();
+        self.bump();
+        self.bump();
+        self.bump();
+
+        let mut raw = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(start.error("Unterminated block string")),
+                Some('"') if self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') => {
+                    self.bump();
+                    self.bump();
+                    self.bump();
+                    return Ok(Token::BlockString(block_string_value(&raw)));
+                }
+                Some('\\')
+                    if self.peek_at(1) == Some('"')
+                        && self.peek_at(2) == Some('"')
+                        && self.peek_at(3) == Some('"') =>
+                {
+                    self.bump();
+                    self.bump();
+                    self.bump();
+                    self.bump();
+                    raw.push_str("\"\"\"");
+                }
+                Some(c) => {
+                    self.bump();
+                    raw.push(c);
+                }
+            }
+        }
+    }
+}
+
+/// Remove the common indentation and leading and trailing blank lines from a block string, as
+/// described by the GraphQL specification.
+fn block_string_value(raw: &str) -> String {
+    let lines: Vec<&str> = raw
+        .split("\r\n")
+        .flat_map(|l| l.split(['\n', '\r']))
+        .collect();
+
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| {
+            let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+            if indent < line.len() {
+                Some(indent)
+            } else {
+                None
+            }
+        })
+        .min()
+        .unwrap_or(0);
+
+    let mut lines: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.len() < common_indent {
+                line
+            } else {
+                &line[common_indent..]
+            }
+        })
+        .collect();
+
+    while lines
+        .first()
+        .is_some_and(|line| line.trim_matches([' ', '\t']).is_empty())
+    {
+        lines.remove(0);
+    }
+    while lines
+        .last()
+        .is_some_and(|line| line.trim_matches([' ', '\t']).is_empty())
+    {
+        lines.pop();
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_string_value() {
+        let raw = "\n    Hello,\n      World!\n\n    Yours,\n      GraphQL.\n  ";
+        assert_eq!(
+            block_string_value(raw),
+            "Hello,\n  World!\n\nYours,\n  GraphQL."
+        );
+    }
+}
@@ -0,0 +1,610 @@
+use super::lexer::{tokenize, Pos, Token};
+use crate::Result;
+use std::fmt;
+
+/// A parsed GraphQL type system document.
+#[derive(Debug)]
+pub struct Document {
+    pub definitions: Vec<Definition>,
+}
+
+#[derive(Debug)]
+pub enum Definition {
+    Schema(SchemaDefinition),
+    Type(TypeDefinition),
+    Directive(DirectiveDefinition),
+    SchemaExtension(SchemaDefinition),
+    TypeExtension(TypeDefinition),
+}
+
+#[derive(Debug)]
+pub struct SchemaDefinition {
+    pub pos: Pos,
+    pub description: Option<String>,
+    pub operation_types: Vec<OperationType>,
+}
+
+#[derive(Debug)]
+pub struct OperationType {
+    pub pos: Pos,
+    pub operation: String,
+    pub type_name: String,
+}
+
+#[derive(Debug)]
+pub struct TypeDefinition {
+    pub pos: Pos,
+    pub kind: TypeDefinitionKind,
+    pub name: String,
+    pub description: Option<String>,
+    pub directives: Vec<Directive>,
+    pub interfaces: Vec<Named>,
+    pub fields: Vec<FieldDefinition>,
+    pub input_fields: Vec<InputValueDefinition>,
+    pub enum_values: Vec<EnumValueDefinition>,
+    pub members: Vec<Named>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeDefinitionKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+}
+
+impl TypeDefinitionKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Scalar => "scalar",
+            Self::Object => "type",
+            Self::Interface => "interface",
+            Self::Union => "union",
+            Self::Enum => "enum",
+            Self::InputObject => "input",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Named {
+    pub pos: Pos,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct FieldDefinition {
+    pub pos: Pos,
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<InputValueDefinition>,
+    pub typ: Type,
+    pub directives: Vec<Directive>,
+}
+
+#[derive(Debug)]
+pub struct InputValueDefinition {
+    pub pos: Pos,
+    pub name: String,
+    pub description: Option<String>,
+    pub typ: Type,
+    pub default_value: Option<Value>,
+    pub directives: Vec<Directive>,
+}
+
+#[derive(Debug)]
+pub struct EnumValueDefinition {
+    pub pos: Pos,
+    pub name: String,
+    pub description: Option<String>,
+    pub directives: Vec<Directive>,
+}
+
+#[derive(Debug)]
+pub struct DirectiveDefinition {
+    pub pos: Pos,
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<InputValueDefinition>,
+    pub is_repeatable: bool,
+    pub locations: Vec<Named>,
+}
+
+#[derive(Debug)]
+pub struct Directive {
+    pub name: String,
+    pub args: Vec<(String, Value)>,
+}
+
+impl Directive {
+    pub fn arg(&self, name: &str) -> Option<&Value> {
+        self.args.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+}
+
+#[derive(Debug)]
+pub enum Type {
+    Named(Named),
+    List(Box<Type>),
+    NonNull(Box<Type>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(String),
+    Float(String),
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(String),
+    List(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Prints the value the same way the reference implementation prints `defaultValue` in an
+/// introspection response.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) | Self::Float(value) => write!(f, "{}", value),
+            Self::String(value) => write!(f, "{}", serde_json::Value::from(value.as_str())),
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::Null => write!(f, "null"),
+            Self::Enum(value) => write!(f, "{}", value),
+            Self::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Parse a GraphQL document that contains only type system definitions and extensions.
+pub fn parse(source: &str) -> Result<Document> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        offset: 0,
+    };
+
+    let mut definitions = Vec::new();
+    while parser.peek() != &Token::Eof {
+        definitions.push(parser.definition()?);
+    }
+
+    Ok(Document { definitions })
+}
+
+struct Parser {
+    tokens: Vec<(Token, Pos)>,
+    offset: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.offset].0
+    }
+
+    fn peek_pos(&self) -> Pos {
+        self.tokens[self.offset].1
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Name(name) if name == keyword)
+    }
+
+    fn bump(&mut self) -> (Token, Pos) {
+        let token = self.tokens[self.offset].clone();
+        if token.0 != Token::Eof {
+            self.offset += 1;
+        }
+        token
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        Err(self.peek_pos().error(format!(
+            "Expected {}, found {}",
+            expected,
+            self.peek().describe()
+        )))
+    }
+
+    fn unexpected_at<T>(&self, pos: Pos, expected: &str) -> Result<T> {
+        Err(pos.error(format!("Expected {}", expected)))
+    }
+
+    fn expect(&mut self, token: Token) -> Result<Pos> {
+        if self.peek() == &token {
+            Ok(self.bump().1)
+        } else {
+            self.unexpected(&token.describe())
+        }
+    }
+
+    fn skip(&mut self, token: Token) -> bool {
+        if self.peek() == &token {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<Pos> {
+        if self.peek_keyword(keyword) {
+            Ok(self.bump().1)
+        } else {
+            self.unexpected(&format!("`{}`", keyword))
+        }
+    }
+
+    fn name(&mut self) -> Result<Named> {
+        match self.peek() {
+            Token::Name(_) => match self.bump() {
+                (Token::Name(name), pos) => Ok(Named { pos, name }),
+                _ => unreachable!(),
+            },
+            _ => self.unexpected("a name"),
+        }
+    }
+
+    fn description(&mut self) -> Option<String> {
+        match self.peek() {
+            Token::String(_) | Token::BlockString(_) => match self.bump().0 {
+                Token::String(s) | Token::BlockString(s) => Some(s),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+
+    fn definition(&mut self) -> Result<Definition> {
+        let description = self.description();
+        let pos = self.peek_pos();
+
+        let keyword = match self.peek() {
+            Token::Name(name) => name.clone(),
+            Token::BraceOpen => {
+                return Err(pos.error("Executable definitions are not supported in a schema"))
+            }
+            _ => return self.unexpected("a definition"),
+        };
+
+        match keyword.as_str() {
+            "schema" => Ok(Definition::Schema(
+                self.schema_definition(description, false)?,
+            )),
+            "directive" => Ok(Definition::Directive(
+                self.directive_definition(description)?,
+            )),
+            "extend" => {
+                if description.is_some() {
+                    return Err(pos.error("Extensions can not have a description"));
+                }
+                self.bump();
+                if self.peek_keyword("schema") {
+                    Ok(Definition::SchemaExtension(
+                        self.schema_definition(None, true)?,
+                    ))
+                } else {
+                    Ok(Definition::TypeExtension(self.type_definition(None, true)?))
+                }
+            }
+            "query" | "mutation" | "subscription" | "fragment" => {
+                Err(pos.error("Executable definitions are not supported in a schema"))
+            }
+            _ => Ok(Definition::Type(self.type_definition(description, false)?)),
+        }
+    }
+
+    fn schema_definition(
+        &mut self,
+        description: Option<String>,
+        is_extension: bool,
+    ) -> Result<SchemaDefinition> {
+        let pos = self.expect_keyword("schema")?;
+        self.directives()?;
+
+        let mut operation_types = Vec::new();
+        if !is_extension || self.peek() == &Token::BraceOpen {
+            self.expect(Token::BraceOpen)?;
+            while !self.skip(Token::BraceClose) {
+                let operation = self.name()?;
+                match operation.name.as_str() {
+                    "query" | "mutation" | "subscription" => {}
+                    _ => return self.unexpected_at(operation.pos, "an operation type"),
+                }
+                self.expect(Token::Colon)?;
+                let type_name = self.name()?;
+                operation_types.push(OperationType {
+                    pos: operation.pos,
+                    operation: operation.name,
+                    type_name: type_name.name,
+                });
+            }
+        }
+
+        Ok(SchemaDefinition {
+            pos,
+            description,
+            operation_types,
+        })
+    }
+
+    fn type_definition(
+        &mut self,
+        description: Option<String>,
+        is_extension: bool,
+    ) -> Result<TypeDefinition> {
+        let keyword = self.name()?;
+        let kind = match keyword.name.as_str() {
+            "scalar" => TypeDefinitionKind::Scalar,
+            "type" => TypeDefinitionKind::Object,
+            "interface" => TypeDefinitionKind::Interface,
+            "union" => TypeDefinitionKind::Union,
+            "enum" => TypeDefinitionKind::Enum,
+            "input" => TypeDefinitionKind::InputObject,
+            _ => return self.unexpected_at(keyword.pos, "a type system definition"),
+        };
+        let name = self.name()?;
+
+        let mut definition = TypeDefinition {
+            pos: name.pos,
+            kind,
+            name: name.name,
+            description,
+            directives: Vec::new(),
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            input_fields: Vec::new(),
+            enum_values: Vec::new(),
+            members: Vec::new(),
+        };
+
+        match kind {
+            TypeDefinitionKind::Scalar => {
+                definition.directives = self.directives()?;
+            }
+            TypeDefinitionKind::Object | TypeDefinitionKind::Interface => {
+                if self.peek_keyword("implements") {
+                    self.bump();
+                    self.skip(Token::Amp);
+                    definition.interfaces.push(self.name()?);
+                    while self.skip(Token::Amp) {
+                        definition.interfaces.push(self.name()?);
+                    }
+                }
+                definition.directives = self.directives()?;
+                if self.skip(Token::BraceOpen) {
+                    while !self.skip(Token::BraceClose) {
+                        definition.fields.push(self.field_definition()?);
+                    }
+                }
+            }
+            TypeDefinitionKind::Union => {
+                definition.directives = self.directives()?;
+                if self.skip(Token::Equals) {
+                    self.skip(Token::Pipe);
+                    definition.members.push(self.name()?);
+                    while self.skip(Token::Pipe) {
+                        definition.members.push(self.name()?);
+                    }
+                }
+            }
+            TypeDefinitionKind::Enum => {
+                definition.directives = self.directives()?;
+                if self.skip(Token::BraceOpen) {
+                    while !self.skip(Token::BraceClose) {
+                        definition.enum_values.push(self.enum_value_definition()?);
+                    }
+                }
+            }
+            TypeDefinitionKind::InputObject => {
+                definition.directives = self.directives()?;
+                if self.skip(Token::BraceOpen) {
+                    while !self.skip(Token::BraceClose) {
+                        definition.input_fields.push(self.input_value_definition()?);
+                    }
+                }
+            }
+        }
+
+        if !is_extension {
+            return Ok(definition);
+        }
+
+        let is_empty = definition.directives.is_empty()
+            && definition.interfaces.is_empty()
+            && definition.fields.is_empty()
+            && definition.input_fields.is_empty()
+            && definition.enum_values.is_empty()
+            && definition.members.is_empty();
+        if is_empty {
+            return self.unexpected(&format!("the body of the {} extension", kind.keyword()));
+        }
+
+        Ok(definition)
+    }
+
+    fn field_definition(&mut self) -> Result<FieldDefinition> {
+        let description = self.description();
+        let name = self.name()?;
+        let args = self.arguments_definition()?;
+        self.expect(Token::Colon)?;
+        let typ = self.typ()?;
+        let directives = self.directives()?;
+
+        Ok(FieldDefinition {
+            pos: name.pos,
+            name: name.name,
+            description,
+            args,
+            typ,
+            directives,
+        })
+    }
+
+    fn arguments_definition(&mut self) -> Result<Vec<InputValueDefinition>> {
+        let mut args = Vec::new();
+        if self.skip(Token::ParenOpen) {
+            while !self.skip(Token::ParenClose) {
+                args.push(self.input_value_definition()?);
+            }
+        }
+        Ok(args)
+    }
+
+    fn input_value_definition(&mut self) -> Result<InputValueDefinition> {
+        let description = self.description();
+        let name = self.name()?;
+        self.expect(Token::Colon)?;
+        let typ = self.typ()?;
+        let default_value = if self.skip(Token::Equals) {
+            Some(self.value()?)
+        } else {
+            None
+        };
+        let directives = self.directives()?;
+
+        Ok(InputValueDefinition {
+            pos: name.pos,
+            name: name.name,
+            description,
+            typ,
+            default_value,
+            directives,
+        })
+    }
+
+    fn enum_value_definition(&mut self) -> Result<EnumValueDefinition> {
+        let description = self.description();
+        let name = self.name()?;
+        if let "true" | "false" | "null" = name.name.as_str() {
+            return Err(name
+                .pos
+                .error(format!("`{}` is not a valid enum value", name.name)));
+        }
+        let directives = self.directives()?;
+
+        Ok(EnumValueDefinition {
+            pos: name.pos,
+            name: name.name,
+            description,
+            directives,
+        })
+    }
+
+    fn directive_definition(&mut self, description: Option<String>) -> Result<DirectiveDefinition> {
+        self.expect_keyword("directive")?;
+        self.expect(Token::At)?;
+        let name = self.name()?;
+        let args = self.arguments_definition()?;
+        let is_repeatable = if self.peek_keyword("repeatable") {
+            self.bump();
+            true
+        } else {
+            false
+        };
+        self.expect_keyword("on")?;
+        self.skip(Token::Pipe);
+        let mut locations = vec![self.name()?];
+        while self.skip(Token::Pipe) {
+            locations.push(self.name()?);
+        }
+
+        Ok(DirectiveDefinition {
+            pos: name.pos,
+            name: name.name,
+            description,
+            args,
+            is_repeatable,
+            locations,
+        })
+    }
+
+    fn directives(&mut self) -> Result<Vec<Directive>> {
+        let mut directives = Vec::new();
+        while self.skip(Token::At) {
+            let name = self.name()?;
+            let mut args = Vec::new();
+            if self.skip(Token::ParenOpen) {
+                while !self.skip(Token::ParenClose) {
+                    let arg_name = self.name()?;
+                    self.expect(Token::Colon)?;
+                    args.push((arg_name.name, self.value()?));
+                }
+            }
+            directives.push(Directive {
+                name: name.name,
+                args,
+            });
+        }
+        Ok(directives)
+    }
+
+    fn typ(&mut self) -> Result<Type> {
+        let typ = if self.skip(Token::BracketOpen) {
+            let inner = self.typ()?;
+            self.expect(Token::BracketClose)?;
+            Type::List(Box::new(inner))
+        } else {
+            Type::Named(self.name()?)
+        };
+
+        if self.skip(Token::Bang) {
+            Ok(Type::NonNull(Box::new(typ)))
+        } else {
+            Ok(typ)
+        }
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        let pos = self.peek_pos();
+        match self.bump().0 {
+            Token::Dollar => Err(pos.error("Variables are not allowed in a schema")),
+            Token::Int(value) => Ok(Value::Int(value)),
+            Token::Float(value) => Ok(Value::Float(value)),
+            Token::String(value) | Token::BlockString(value) => Ok(Value::String(value)),
+            Token::Name(name) => Ok(match name.as_str() {
+                "true" => Value::Boolean(true),
+                "false" => Value::Boolean(false),
+                "null" => Value::Null,
+                _ => Value::Enum(name),
+            }),
+            Token::BracketOpen => {
+                let mut values = Vec::new();
+                while !self.skip(Token::BracketClose) {
+                    values.push(self.value()?);
+                }
+                Ok(Value::List(values))
+            }
+            Token::BraceOpen => {
+                let mut fields = Vec::new();
+                while !self.skip(Token::BraceClose) {
+                    let name = self.name()?;
+                    self.expect(Token::Colon)?;
+                    fields.push((name.name, self.value()?));
+                }
+                Ok(Value::Object(fields))
+            }
+            token => Err(pos.error(format!("Expected a value, found {}", token.describe()))),
+        }
+    }
+}
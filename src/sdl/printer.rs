@@ -0,0 +1,350 @@
+use super::{BUILT_IN_SCALARS, DEFAULT_DEPRECATION_REASON};
+use crate::schema;
+
+/// The directives every GraphQL server provides, which are left out of the printed schema
+const BUILT_IN_DIRECTIVES: &[&str] = &["include", "skip", "deprecated", "specifiedBy", "oneOf"];
+
+/// Print a schema as an SDL document.
+///
+/// Directives and types are sorted by name. Built-in scalars, built-in directives and
+/// introspection types are left out, as is the schema definition when it only uses the default
+/// root type names.
+pub fn print_schema(schema: &schema::Schema) -> String {
+    let mut definitions = Vec::new();
+
+    if let Some(definition) = print_schema_definition(schema) {
+        definitions.push(definition);
+    }
+
+    let mut directives: Vec<&schema::Directive> = schema
+        .directives
+        .iter()
+        .filter(|directive| !BUILT_IN_DIRECTIVES.contains(&directive.name.as_str()))
+        .collect();
+    directives.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions.extend(directives.into_iter().map(print_directive));
+
+    let mut types: Vec<&schema::FullType> = schema
+        .types
+        .iter()
+        .filter(|typ| !typ.name.starts_with("__") && !BUILT_IN_SCALARS.contains(&typ.name.as_str()))
+        .collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions.extend(types.into_iter().map(print_type));
+
+    let mut sdl = definitions.join("\n\n");
+    sdl.push('\n');
+    sdl
+}
+
+fn print_schema_definition(schema: &schema::Schema) -> Option<String> {
+    let roots = [
+        ("query", &schema.query_type, "Query"),
+        ("mutation", &schema.mutation_type, "Mutation"),
+        ("subscription", &schema.subscription_type, "Subscription"),
+    ];
+
+    let is_conventional = roots.iter().all(|(_, root, default_name)| match root {
+        Some(root) => root.name == *default_name,
+        None => true,
+    });
+    if is_conventional && schema.description.is_none() {
+        return None;
+    }
+
+    let mut out = print_description(schema.description.as_deref(), "");
+    out.push_str("schema {\n");
+    for (operation, root, _) in &roots {
+        if let Some(root) = root {
+            out.push_str(&format!("  {}: {}\n", operation, root.name));
+        }
+    }
+    out.push('}');
+    Some(out)
+}
+
+/// Print a single type definition as SDL.
+pub fn print_type(typ: &schema::FullType) -> String {
+    let mut out = print_description(typ.description.as_deref(), "");
+
+    match typ.kind {
+        schema::Kind::Scalar => {
+            out.push_str(&format!("scalar {}", typ.name));
+            if let Some(ref url) = typ.specified_by_url {
+                out.push_str(&format!(" @specifiedBy(url: {})", print_string(url)));
+            }
+        }
+        schema::Kind::Object | schema::Kind::Interface => {
+            let keyword = if typ.kind == schema::Kind::Object {
+                "type"
+            } else {
+                "interface"
+            };
+            out.push_str(&format!("{} {}", keyword, typ.name));
+            let interfaces: Vec<String> = typ
+                .interfaces
+                .iter()
+                .flatten()
+                .map(|interface| interface.to_string())
+                .collect();
+            if !interfaces.is_empty() {
+                out.push_str(&format!(" implements {}", interfaces.join(" & ")));
+            }
+            let fields: Vec<String> = typ.fields.iter().flatten().map(print_field).collect();
+            out.push_str(&print_block(&fields));
+        }
+        schema::Kind::Union => {
+            out.push_str(&format!("union {}", typ.name));
+            let possible_types: Vec<String> = typ
+                .possible_types
+                .iter()
+                .flatten()
+                .map(|possible_type| possible_type.to_string())
+                .collect();
+            if !possible_types.is_empty() {
+                out.push_str(&format!(" = {}", possible_types.join(" | ")));
+            }
+        }
+        schema::Kind::Enum => {
+            out.push_str(&format!("enum {}", typ.name));
+            let values: Vec<String> = typ
+                .enum_values
+                .iter()
+                .flatten()
+                .map(|value| {
+                    format!(
+                        "{}  {}{}",
+                        print_description(value.description.as_deref(), "  "),
+                        value.name,
+                        print_deprecated(value.is_deprecated, value.deprecation_reason.as_deref())
+                    )
+                })
+                .collect();
+            out.push_str(&print_block(&values));
+        }
+        schema::Kind::InputObject => {
+            out.push_str(&format!("input {}", typ.name));
+            if typ.is_one_of == Some(true) {
+                out.push_str(" @oneOf");
+            }
+            let fields: Vec<String> = typ
+                .input_fields
+                .iter()
+                .flatten()
+                .map(|input_field| print_input_value(input_field, "  "))
+                .collect();
+            out.push_str(&print_block(&fields));
+        }
+        schema::Kind::List | schema::Kind::NonNull => {}
+    }
+
+    out
+}
+
+fn print_directive(directive: &schema::Directive) -> String {
+    let mut out = print_description(directive.description.as_deref(), "");
+    out.push_str(&format!(
+        "directive @{}{}",
+        directive.name,
+        print_args(&directive.args, "")
+    ));
+    if directive.is_repeatable == Some(true) {
+        out.push_str(" repeatable");
+    }
+    out.push_str(&format!(" on {}", directive.locations.join(" | ")));
+    out
+}
+
+fn print_field(field: &schema::Field) -> String {
+    format!(
+        "{}  {}{}: {}{}",
+        print_description(field.description.as_deref(), "  "),
+        field.name,
+        print_args(&field.args, "  "),
+        field.typ,
+        print_deprecated(field.is_deprecated, field.deprecation_reason.as_deref())
+    )
+}
+
+/// Print an argument list. Arguments go on one line unless one of them has a description.
+fn print_args(args: &[schema::InputValue], indent: &str) -> String {
+    if args.is_empty() {
+        return String::new();
+    }
+
+    if args.iter().all(|arg| arg.description.is_none()) {
+        let args: Vec<String> = args.iter().map(|arg| print_input_value(arg, "")).collect();
+        return format!("({})", args.join(", "));
+    }
+
+    let inner_indent = format!("{}  ", indent);
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| print_input_value(arg, &inner_indent))
+        .collect();
+    format!("(\n{}\n{})", args.join("\n"), indent)
+}
+
+fn print_input_value(input_value: &schema::InputValue, indent: &str) -> String {
+    let mut out = print_description(input_value.description.as_deref(), indent);
+    out.push_str(&format!(
+        "{}{}: {}",
+        indent, input_value.name, input_value.typ
+    ));
+    if let Some(ref default_value) = input_value.default_value {
+        out.push_str(&format!(" = {}", default_value));
+    }
+    out.push_str(&print_deprecated(
+        input_value.is_deprecated,
+        input_value.deprecation_reason.as_deref(),
+    ));
+    out
+}
+
+/// Print the already indented members of a type's body between braces, or nothing if there are
+/// none.
+fn print_block(members: &[String]) -> String {
+    if members.is_empty() {
+        return String::new();
+    }
+
+    format!(" {{\n{}\n}}", members.join("\n"))
+}
+
+fn print_deprecated(is_deprecated: bool, reason: Option<&str>) -> String {
+    match reason {
+        _ if !is_deprecated => String::new(),
+        None | Some(DEFAULT_DEPRECATION_REASON) => " @deprecated".to_string(),
+        Some(reason) => format!(" @deprecated(reason: {})", print_string(reason)),
+    }
+}
+
+/// Print a description as a block string on the lines before a definition.
+fn print_description(description: Option<&str>, indent: &str) -> String {
+    let description = match description {
+        Some(description) if !description.is_empty() => description,
+        _ => return String::new(),
+    };
+
+    let escaped = description.replace("\"\"\"", "\\\"\"\"");
+    if !escaped.contains('\n') && !escaped.ends_with('"') && !escaped.ends_with('\\') {
+        return format!("{}\"\"\"{}\"\"\"\n", indent, escaped);
+    }
+
+    let mut out = format!("{}\"\"\"\n", indent);
+    for line in escaped.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("{}{}\n", indent, line));
+        }
+    }
+    out.push_str(&format!("{}\"\"\"\n", indent));
+    out
+}
+
+/// Print a GraphQL string literal.
+fn print_string(value: &str) -> String {
+    // JSON string escaping is a subset of what GraphQL string literals accept
+    serde_json::Value::from(value).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_schema_round_trip() {
+        let source = r#"schema {
+  query: Root
+}
+
+directive @auth(role: String!) repeatable on FIELD_DEFINITION | OBJECT
+
+input Filter @oneOf {
+  id: ID
+  name: String @deprecated
+}
+
+interface Node {
+  id: ID!
+}
+
+enum Role {
+  ADMIN
+  GUEST @deprecated(reason: "Sign up instead")
+}
+
+"""The root of all queries"""
+type Root implements Node {
+  id: ID!
+  """
+  Look up users.
+
+  Returns an empty list when nobody matches.
+  """
+  users(
+    """How many to return"""
+    first: Int = 10
+    after: String
+  ): [User!]!
+  oldName: String @deprecated
+  name(upper: Boolean = false): String @deprecated(reason: "Use `displayName`")
+}
+
+union SearchResult = Root | User
+
+scalar URL @specifiedBy(url: "https://tools.ietf.org/html/rfc3986")
+
+type User implements Node {
+  id: ID!
+  role: Role
+}
+"#;
+
+        let schema = crate::sdl::parse_schema(source).unwrap();
+        assert_eq!(print_schema(&schema), source);
+    }
+
+    #[test]
+    fn test_print_type_blocks() {
+        let source = r#"interface Node {
+  id: ID!
+}
+
+enum Order {
+  NAME
+}
+
+"""A person"""
+type User implements Node {
+  id: ID!
+  friends(first: Int = 10, orderBy: [Order!] = [NAME]): [User!]! @deprecated(reason: "Use `contacts`")
+}
+"#;
+
+        // The way schema.graphql is written for an endpoint: from an introspection response
+        let schema = crate::sdl::parse_schema(source).unwrap();
+        let response = schema::GraphQLResponse::from_json(
+            serde_json::from_str(&schema.to_introspection_json().unwrap()).unwrap(),
+        )
+        .unwrap();
+        let schema = response.data.unwrap().schema;
+        let sdl = print_schema(&schema);
+        assert_eq!(sdl, source);
+
+        // Every type's page shows the same block the whole schema has for it
+        let user = schema.types.iter().find(|typ| typ.name == "User").unwrap();
+        assert_eq!(
+            print_type(user),
+            r#""""A person"""
+type User implements Node {
+  id: ID!
+  friends(first: Int = 10, orderBy: [Order!] = [NAME]): [User!]! @deprecated(reason: "Use `contacts`")
+}"#
+        );
+        for typ in schema.types.iter().filter(|typ| !typ.is_built_in()) {
+            assert!(sdl.contains(&print_type(typ)), "{}", typ.name);
+        }
+    }
+}
@@ -8,17 +8,26 @@ use serde::{
 pub struct SearchIndex(Vec<SearchIndexItem>);
 
 impl SearchIndex {
-    pub fn build(schema: &schema::Schema) -> Self {
+    /// Build the index from every type that `include` accepts, and every directive.
+    pub fn build(schema: &schema::Schema, include: impl Fn(&schema::FullType) -> bool) -> Self {
         let mut items = Vec::new();
 
-        for typ in &schema.types {
-            Self::build_type(typ, &mut items);
+        for typ in schema.types.iter().filter(|typ| include(typ)) {
+            Self::build_type(typ, schema.root_operation(typ), &mut items);
+        }
+
+        for directive in &schema.directives {
+            Self::build_directive(directive, &mut items);
         }
 
         SearchIndex(items)
     }
 
-    fn build_type(typ: &schema::FullType, items: &mut Vec<SearchIndexItem>) {
+    fn build_type(
+        typ: &schema::FullType,
+        root_operation: Option<&str>,
+        items: &mut Vec<SearchIndexItem>,
+    ) {
         let name = &typ.name;
         let kind = match typ.kind {
             schema::Kind::Union => "union",
@@ -30,12 +39,21 @@ impl SearchIndex {
             schema::Kind::List | schema::Kind::NonNull => return,
         };
 
+        let mut index = vec![name.to_lowercase()];
+        // Make root types findable by the operation they are the root of
+        if let Some(root_operation) = root_operation {
+            if root_operation != index[0] {
+                index.push(root_operation.to_string());
+            }
+        }
+
         let item = SearchIndexItem {
-            index: vec![name.to_lowercase()],
+            index,
             name: name.to_string(),
             kind: kind.to_string(),
             parent_name: None,
             parent_kind: None,
+            is_deprecated: false,
         };
 
         items.push(item);
@@ -57,6 +75,33 @@ impl SearchIndex {
         }
     }
 
+    fn build_directive(directive: &schema::Directive, items: &mut Vec<SearchIndexItem>) {
+        let name = &directive.name;
+        let item = SearchIndexItem {
+            index: vec![name.to_lowercase(), format!("@{}", name.to_lowercase())],
+            name: name.to_string(),
+            kind: "directive".to_string(),
+            parent_name: None,
+            parent_kind: None,
+            is_deprecated: false,
+        };
+
+        items.push(item);
+
+        for arg in &directive.args {
+            let item = SearchIndexItem {
+                index: vec![arg.name.to_lowercase()],
+                name: arg.name.to_string(),
+                kind: "arg".to_string(),
+                parent_name: Some(name.to_string()),
+                parent_kind: Some("directive".to_string()),
+                is_deprecated: arg.is_deprecated,
+            };
+
+            items.push(item);
+        }
+    }
+
     fn build_field(
         field: &schema::Field,
         parent_name: &str,
@@ -69,6 +114,7 @@ impl SearchIndex {
             kind: "field".to_string(),
             parent_name: Some(parent_name.to_string()),
             parent_kind: Some(parent_kind.to_string()),
+            is_deprecated: field.is_deprecated,
         };
 
         items.push(item);
@@ -89,6 +135,7 @@ impl SearchIndex {
             kind: "enum_value".to_string(),
             parent_name: Some(parent_name.to_string()),
             parent_kind: Some(parent_kind.to_string()),
+            is_deprecated: enum_value.is_deprecated,
         };
 
         items.push(item);
@@ -106,6 +153,7 @@ impl SearchIndex {
             kind: "input_field".to_string(),
             parent_name: Some(parent_name.to_string()),
             parent_kind: Some(parent_kind.to_string()),
+            is_deprecated: input_field.is_deprecated,
         };
 
         items.push(item);
@@ -118,6 +166,7 @@ pub struct SearchIndexItem {
     kind: String,
     parent_name: Option<String>,
     parent_kind: Option<String>,
+    is_deprecated: bool,
 }
 
 impl Serialize for SearchIndexItem {
@@ -134,6 +183,10 @@ impl Serialize for SearchIndexItem {
         {
             seq.serialize_element(parent_name)?;
             seq.serialize_element(parent_kind)?;
+            // Only children can be deprecated, so the flag is only ever present after the parent
+            if self.is_deprecated {
+                seq.serialize_element(&true)?;
+            }
         }
         seq.end()
     }
@@ -144,6 +197,28 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_root_types_indexed_by_operation() {
+        let schema = crate::sdl::parse_schema(
+            r#"
+            schema { query: Query subscription: Events }
+            type Query { ping: Boolean }
+            type Events { pinged: Boolean }
+            "#,
+        )
+        .unwrap();
+        let SearchIndex(items) = SearchIndex::build(&schema, |_| true);
+        let index = |name: &str| {
+            items
+                .iter()
+                .find(|item| item.name == name && item.parent_name.is_none())
+                .map(|item| item.index.clone())
+                .unwrap()
+        };
+        assert_eq!(index("Query"), vec!["query"]);
+        assert_eq!(index("Events"), vec!["events", "subscription"]);
+    }
+
     #[test]
     fn test_serialize_with_parent() {
         let item = SearchIndexItem {
@@ -152,6 +227,7 @@ mod tests {
             kind: "enumitem".to_string(),
             parent_name: Some("AccountType".to_string()),
             parent_kind: Some("enum".to_string()),
+            is_deprecated: false,
         };
 
         let value = serde_json::to_value(&item).unwrap();
@@ -175,9 +251,28 @@ mod tests {
             kind: "enum".to_string(),
             parent_name: None,
             parent_kind: None,
+            is_deprecated: false,
         };
 
         let value = serde_json::to_value(&item).unwrap();
         assert_eq!(value, json!([["accounttype"], "AccountType", "enum"]));
     }
+
+    #[test]
+    fn test_serialize_deprecated() {
+        let item = SearchIndexItem {
+            index: vec!["oldname".to_string()],
+            name: "oldName".to_string(),
+            kind: "field".to_string(),
+            parent_name: Some("User".to_string()),
+            parent_kind: Some("object".to_string()),
+            is_deprecated: true,
+        };
+
+        let value = serde_json::to_value(&item).unwrap();
+        assert_eq!(
+            value,
+            json!([["oldname"], "oldName", "field", "User", "object", true])
+        );
+    }
 }
@@ -0,0 +1,498 @@
+//! Numbers describing the size and shape of a schema, for reviewing API designs.
+//!
+//! Introspection types and built-in scalars are left out of every statistic.
+
+use super::schema::{self, Kind, TypeRef};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// How many of the most referenced types to list
+const MOST_REFERENCED: usize = 10;
+/// How many of the types with the most fields to list
+const LARGEST: usize = 5;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    /// How many types there are of each kind
+    pub kinds: BTreeMap<Kind, usize>,
+    /// How many fields objects and interfaces have
+    pub fields_per_type: Distribution,
+    pub deepest_input: InputDepth,
+    /// The fields, arguments and input fields whose type is a list of lists, like `[[Int]]`
+    pub lists_of_lists: Vec<String>,
+    pub output_nullability: Nullability,
+    pub input_nullability: Nullability,
+    /// Groups of types that refer to each other in a circle, largest first
+    pub cycles: Vec<Vec<TypeName>>,
+    pub most_referenced: Vec<TypeCount>,
+}
+
+/// Summary statistics of a count per type
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distribution {
+    pub min: usize,
+    pub median: usize,
+    /// Rounded to one decimal
+    pub mean: f64,
+    pub max: usize,
+    pub largest: Vec<TypeCount>,
+}
+
+/// How many type references allow `null`
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Nullability {
+    pub nullable: usize,
+    pub non_null: usize,
+    /// The percentage of non-null references, rounded to one decimal
+    pub non_null_percent: f64,
+}
+
+/// How deeply input objects nest in each other's fields
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDepth {
+    /// Whether some input objects contain themselves, so that inputs can nest without limit
+    pub unbounded: bool,
+    /// The input objects that contain themselves if unbounded, otherwise the longest chain of
+    /// input objects nested in each other's fields
+    pub types: Vec<TypeName>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeName {
+    pub name: String,
+    pub kind: Kind,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TypeCount {
+    #[serde(flatten)]
+    pub typ: TypeName,
+    pub count: usize,
+}
+
+impl Stats {
+    pub fn new(schema: &schema::Schema) -> Self {
+        let types: Vec<&schema::FullType> = schema
+            .types
+            .iter()
+            .filter(|typ| !typ.is_built_in())
+            .collect();
+        let by_name: HashMap<&str, &schema::FullType> =
+            types.iter().map(|typ| (typ.name.as_str(), *typ)).collect();
+
+        let mut kinds = BTreeMap::new();
+        for typ in &types {
+            *kinds.entry(typ.kind).or_insert(0) += 1;
+        }
+
+        let mut output_nullability = Nullability::default();
+        let mut input_nullability = Nullability::default();
+        let mut lists_of_lists = Vec::new();
+        let mut references: HashMap<&str, usize> = HashMap::new();
+        for typ in &types {
+            for field in typ.fields.iter().flatten() {
+                output_nullability.count(&field.typ);
+                for arg in &field.args {
+                    input_nullability.count(&arg.typ);
+                }
+            }
+            for input_field in typ.input_fields.iter().flatten() {
+                input_nullability.count(&input_field.typ);
+            }
+            for (path, type_ref) in typ.type_refs() {
+                if is_list_of_lists(type_ref) {
+                    lists_of_lists.push(path);
+                }
+                if let Some(ref name) = type_ref.named_type().name {
+                    if by_name.contains_key(name.as_str()) {
+                        *references.entry(name).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        output_nullability.finish();
+        input_nullability.finish();
+        lists_of_lists.sort();
+
+        let mut most_referenced: Vec<TypeCount> = references
+            .into_iter()
+            .map(|(name, count)| TypeCount {
+                typ: type_name(by_name[name]),
+                count,
+            })
+            .collect();
+        sort_counts(&mut most_referenced);
+        most_referenced.truncate(MOST_REFERENCED);
+
+        Self {
+            kinds,
+            fields_per_type: Distribution::new(
+                types
+                    .iter()
+                    .filter(|typ| typ.fields.is_some())
+                    .map(|typ| TypeCount {
+                        typ: type_name(typ),
+                        count: typ.fields.iter().flatten().count(),
+                    })
+                    .collect(),
+            ),
+            deepest_input: deepest_input(&types, &by_name),
+            lists_of_lists,
+            output_nullability,
+            input_nullability,
+            cycles: cycles(&types, &by_name),
+            most_referenced,
+        }
+    }
+}
+
+impl Distribution {
+    fn new(mut counts: Vec<TypeCount>) -> Self {
+        if counts.is_empty() {
+            return Self::default();
+        }
+
+        sort_counts(&mut counts);
+        let total: usize = counts.iter().map(|c| c.count).sum();
+        let mean = round(total as f64 / counts.len() as f64);
+        let median = counts[counts.len() / 2].count;
+        let max = counts[0].count;
+        let min = counts[counts.len() - 1].count;
+        counts.truncate(LARGEST);
+
+        Self {
+            min,
+            median,
+            mean,
+            max,
+            largest: counts,
+        }
+    }
+}
+
+impl Nullability {
+    fn count(&mut self, type_ref: &TypeRef) {
+        if type_ref.kind == Kind::NonNull {
+            self.non_null += 1;
+        } else {
+            self.nullable += 1;
+        }
+    }
+
+    fn finish(&mut self) {
+        let total = self.nullable + self.non_null;
+        if total > 0 {
+            self.non_null_percent = round(self.non_null as f64 * 100.0 / total as f64);
+        }
+    }
+}
+
+fn type_name(typ: &schema::FullType) -> TypeName {
+    TypeName {
+        name: typ.name.clone(),
+        kind: typ.kind,
+    }
+}
+
+/// Sort by count, largest first, then by name
+fn sort_counts(counts: &mut [TypeCount]) {
+    counts.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.typ.name.cmp(&b.typ.name))
+    });
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn is_list_of_lists(type_ref: &TypeRef) -> bool {
+    let mut lists = 0;
+    let mut current = Some(type_ref);
+    while let Some(type_ref) = current {
+        if type_ref.kind == Kind::List {
+            lists += 1;
+        }
+        current = type_ref.of_type.as_deref();
+    }
+    lists > 1
+}
+
+/// The types referred to by the type, by name, in the order they appear
+fn neighbors<'a>(
+    typ: &'a schema::FullType,
+    by_name: &HashMap<&str, &'a schema::FullType>,
+) -> Vec<&'a schema::FullType> {
+    typ.type_refs()
+        .into_iter()
+        .filter_map(|(_, type_ref)| by_name.get(type_ref.named_type().name.as_deref()?))
+        .copied()
+        .collect()
+}
+
+/// How deeply input objects nest.
+///
+/// This is computed on the condensation of the input objects' graph: a strongly connected
+/// component with a cycle makes the depth unbounded, and otherwise the components form a DAG
+/// whose longest path is the deepest chain.
+fn deepest_input(
+    types: &[&schema::FullType],
+    by_name: &HashMap<&str, &schema::FullType>,
+) -> InputDepth {
+    let inputs: Vec<&schema::FullType> = types
+        .iter()
+        .filter(|typ| typ.kind == Kind::InputObject)
+        .copied()
+        .collect();
+    let edges = edges(&inputs, by_name);
+    let components = strongly_connected_components(&edges);
+
+    let mut recursive: Vec<TypeName> = components
+        .iter()
+        .filter(|component| is_cycle(component, &edges))
+        .flatten()
+        .map(|&node| type_name(inputs[node]))
+        .collect();
+    if !recursive.is_empty() {
+        recursive.sort_by(|a, b| a.name.cmp(&b.name));
+        return InputDepth {
+            unbounded: true,
+            types: recursive,
+        };
+    }
+
+    // Without cycles every component is a single input object, and each comes after the input
+    // objects it contains, so their depths are known by the time it is reached
+    let mut depth = vec![0; inputs.len()];
+    let mut deeper: Vec<Option<usize>> = vec![None; inputs.len()];
+    for component in &components {
+        let node = component[0];
+        for &next in &edges[node] {
+            if deeper[node].is_none_or(|deeper| depth[next] > depth[deeper]) {
+                deeper[node] = Some(next);
+            }
+        }
+        depth[node] = 1 + deeper[node].map_or(0, |deeper| depth[deeper]);
+    }
+
+    let mut chain = Vec::new();
+    let mut current = (0..inputs.len()).reduce(|a, b| if depth[b] > depth[a] { b } else { a });
+    while let Some(node) = current {
+        chain.push(type_name(inputs[node]));
+        current = deeper[node];
+    }
+    InputDepth {
+        unbounded: false,
+        types: chain,
+    }
+}
+
+/// The strongly connected components of the type graph that contain a cycle
+fn cycles(
+    types: &[&schema::FullType],
+    by_name: &HashMap<&str, &schema::FullType>,
+) -> Vec<Vec<TypeName>> {
+    let edges = edges(types, by_name);
+    let mut components: Vec<Vec<TypeName>> = strongly_connected_components(&edges)
+        .into_iter()
+        .filter(|component| is_cycle(component, &edges))
+        .map(|component| {
+            let mut component: Vec<TypeName> = component
+                .into_iter()
+                .map(|node| type_name(types[node]))
+                .collect();
+            component.sort_by(|a, b| a.name.cmp(&b.name));
+            component
+        })
+        .collect();
+    components.sort_by(|a, b| {
+        b.len()
+            .cmp(&a.len())
+            .then_with(|| a[0].name.cmp(&b[0].name))
+    });
+    components
+}
+
+/// For each of the types, the positions in `types` of the types it refers to
+fn edges(
+    types: &[&schema::FullType],
+    by_name: &HashMap<&str, &schema::FullType>,
+) -> Vec<Vec<usize>> {
+    let positions: HashMap<&str, usize> = types
+        .iter()
+        .enumerate()
+        .map(|(position, typ)| (typ.name.as_str(), position))
+        .collect();
+    types
+        .iter()
+        .map(|typ| {
+            neighbors(typ, by_name)
+                .into_iter()
+                .filter_map(|next| positions.get(next.name.as_str()).copied())
+                .collect()
+        })
+        .collect()
+}
+
+/// Whether a strongly connected component contains a cycle, rather than being a single node that
+/// doesn't refer to itself
+fn is_cycle(component: &[usize], edges: &[Vec<usize>]) -> bool {
+    component.len() > 1 || edges[component[0]].contains(&component[0])
+}
+
+/// The strongly connected components of a graph, found with Tarjan's algorithm. It keeps its own
+/// stack rather than recursing, so that long chains of types can't overflow the call stack.
+///
+/// Every component comes after the components it has edges to.
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut index: Vec<Option<usize>> = vec![None; edges.len()];
+    let mut low_link = vec![0; edges.len()];
+    let mut on_stack = vec![false; edges.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..edges.len() {
+        if index[root].is_some() {
+            continue;
+        }
+
+        // The nodes being visited, with how many of their edges have been followed
+        let mut visiting = vec![(root, 0)];
+        while let Some(&(node, edge)) = visiting.last() {
+            if edge == 0 {
+                index[node] = Some(next_index);
+                low_link[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            if let Some(&next) = edges[node].get(edge) {
+                visiting.last_mut().unwrap().1 += 1;
+                match index[next] {
+                    None => visiting.push((next, 0)),
+                    Some(next_index) if on_stack[next] => {
+                        low_link[node] = low_link[node].min(next_index);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            visiting.pop();
+            if let Some(&(parent, _)) = visiting.last() {
+                low_link[parent] = low_link[parent].min(low_link[node]);
+            }
+            if Some(low_link[node]) == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl::parse_schema;
+
+    #[test]
+    fn test_stats() {
+        let schema = parse_schema(
+            r#"
+            type Query { user(id: ID!): User users(filter: Filter): [User!]! matrix: [[Int]] }
+            type User { id: ID! friends: [User!] team: Team }
+            type Team { members: [User!]! }
+            type Leaf { name: String }
+            input Filter { name: String and: Filter range: Range }
+            input Range { from: Int! to: Int! bounds: Bounds }
+            input Bounds { inclusive: Boolean }
+            "#,
+        )
+        .unwrap();
+
+        let stats = Stats::new(&schema);
+        let names =
+            |types: &[TypeName]| -> Vec<String> { types.iter().map(|t| t.name.clone()).collect() };
+
+        assert_eq!(stats.kinds[&Kind::Object], 4);
+        assert_eq!(stats.kinds[&Kind::InputObject], 3);
+        assert_eq!(stats.fields_per_type.max, 3);
+        assert_eq!(stats.fields_per_type.min, 1);
+        assert_eq!(stats.fields_per_type.mean, 2.0);
+        assert!(stats.deepest_input.unbounded);
+        assert_eq!(names(&stats.deepest_input.types), vec!["Filter"]);
+        assert_eq!(stats.lists_of_lists, vec!["Query.matrix"]);
+        assert_eq!(stats.output_nullability.non_null, 3);
+        assert_eq!(stats.output_nullability.nullable, 5);
+        assert_eq!(stats.input_nullability.non_null_percent, 33.3);
+
+        let cycles: Vec<Vec<String>> = stats.cycles.iter().map(|c| names(c)).collect();
+        assert_eq!(cycles, vec![vec!["Team", "User"], vec!["Filter"]]);
+        assert_eq!(stats.most_referenced[0].typ.name, "User");
+        assert_eq!(stats.most_referenced[0].count, 4);
+    }
+
+    #[test]
+    fn test_deepest_input() {
+        // The longest chain doesn't start at the first input object, and goes through an input
+        // object that an earlier one contains too
+        let schema = parse_schema(
+            r#"
+            type Query { search(a: A, z: Z): Int }
+            input A { b: B }
+            input B { leaf: Leaf }
+            input Z { y: Y }
+            input Y { x: X }
+            input X { b: B }
+            input Leaf { value: Int }
+            "#,
+        )
+        .unwrap();
+        let depth = Stats::new(&schema).deepest_input;
+        let names: Vec<&str> = depth.types.iter().map(|t| t.name.as_str()).collect();
+        assert!(!depth.unbounded);
+        assert_eq!(names, vec!["Z", "Y", "X", "B", "Leaf"]);
+
+        // Nesting through a cycle has no limit, however the cycle is reached
+        let schema = parse_schema(
+            r#"
+            type Query { search(a: A): Int }
+            input A { b: B }
+            input B { c: C }
+            input C { b: B }
+            "#,
+        )
+        .unwrap();
+        let depth = Stats::new(&schema).deepest_input;
+        let names: Vec<&str> = depth.types.iter().map(|t| t.name.as_str()).collect();
+        assert!(depth.unbounded);
+        assert_eq!(names, vec!["B", "C"]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components_long_chain() {
+        // Far longer than the call stack would allow if the traversal recursed
+        let edges: Vec<Vec<usize>> = (0..200_000)
+            .map(|node| vec![node + 1])
+            .chain([vec![0]])
+            .collect();
+        let components = strongly_connected_components(&edges);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), 200_001);
+    }
+}
@@ -53,6 +53,7 @@ const NAME = 1
 const TYPE = 2
 const PARENT_NAME = 3
 const PARENT_TYPE = 4
+const DEPRECATED = 5
 
 function scoreIndexItem(input, item) {
     const index = item[INDEX]
@@ -113,6 +114,10 @@ function renderItem(item) {
     const code = document.createElement('code')
     h3.appendChild(code)
 
+    if (item[DEPRECATED]) {
+        h3.classList.add('deprecated')
+    }
+
     if (item[PARENT_NAME]) {
         // This is a child
         const parentLink = document.createElement('a')
@@ -0,0 +1,397 @@
+//! Check that a schema is internally consistent before documenting it.
+//!
+//! Introspection responses are produced by servers, and a buggy one can describe a schema that
+//! couldn't exist: references to types it doesn't list, unions of scalars, objects that don't
+//! implement the interfaces they claim to. Problems that would leave dead links or wrong pages in
+//! the documentation are errors; other violations of the specification are warnings.
+
+use super::schema::{self, Kind, TypeRef};
+use std::collections::HashSet;
+use std::fmt;
+
+/// How much a diagnostic matters for rendering the documentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single problem with the schema
+#[derive(Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Where in the schema the problem is, like `User.name` or `User (interfaces)`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Check the schema, returning every problem found.
+pub fn validate(schema: &schema::Schema) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        schema,
+        diagnostics: Vec::new(),
+    };
+    validator.validate();
+    validator.diagnostics
+}
+
+struct Validator<'a> {
+    schema: &'a schema::Schema,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(&mut self) {
+        self.unique_names();
+        self.roots();
+        for (path, type_ref) in self.schema.type_refs() {
+            self.type_ref(path, type_ref);
+        }
+        for typ in &self.schema.types {
+            self.positions(typ);
+            match typ.kind {
+                Kind::Object | Kind::Interface => self.implementations(typ),
+                Kind::Union => self.union_members(typ),
+                _ => {}
+            }
+            if typ.kind == Kind::Interface {
+                self.interface_possible_types(typ);
+            }
+        }
+    }
+
+    fn error(&mut self, path: String, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            path,
+            message,
+        });
+    }
+
+    fn warning(&mut self, path: String, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            path,
+            message,
+        });
+    }
+
+    fn unique_names(&mut self) {
+        let mut seen = HashSet::new();
+        for typ in &self.schema.types {
+            if !seen.insert(typ.name.as_str()) {
+                self.error(
+                    typ.name.clone(),
+                    format!("The type `{}` is defined more than once", typ.name),
+                );
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for directive in &self.schema.directives {
+            if !seen.insert(directive.name.as_str()) {
+                self.error(
+                    format!("@{}", directive.name),
+                    format!(
+                        "The directive `@{}` is defined more than once",
+                        directive.name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn roots(&mut self) {
+        let roots = [
+            ("query", &self.schema.query_type),
+            ("mutation", &self.schema.mutation_type),
+            ("subscription", &self.schema.subscription_type),
+        ];
+        for (operation, root) in &roots {
+            let root = match root {
+                Some(root) => root,
+                None => continue,
+            };
+            match self.schema.types.iter().find(|t| t.name == root.name) {
+                None => self.error(
+                    format!("schema ({})", operation),
+                    format!("The {} root type `{}` does not exist", operation, root.name),
+                ),
+                Some(typ) if typ.kind != Kind::Object => self.error(
+                    format!("schema ({})", operation),
+                    format!(
+                        "The {} root type `{}` is not an object",
+                        operation, root.name
+                    ),
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// The named type at the bottom of the reference must exist and be of the kind the reference
+    /// says it is, because that is where the link to its page goes.
+    fn type_ref(&mut self, path: String, type_ref: &TypeRef) {
+        let named = type_ref.named_type();
+        let name = match named.name {
+            Some(ref name) => name,
+            None => return,
+        };
+        match self.schema.find_type(named) {
+            None => self.error(path, format!("The type `{}` does not exist", name)),
+            Some(typ) if typ.kind != named.kind => self.error(
+                path,
+                format!(
+                    "`{}` is referred to as {} but is {}",
+                    name,
+                    describe(named.kind),
+                    describe(typ.kind)
+                ),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    /// Fields must have output types, and arguments and input fields input types.
+    fn positions(&mut self, typ: &schema::FullType) {
+        for field in typ.fields.iter().flatten() {
+            let kind = field.typ.named_type().kind;
+            if kind == Kind::InputObject {
+                self.warning(
+                    format!("{}.{}", typ.name, field.name),
+                    "A field cannot return an input object".to_string(),
+                );
+            }
+            for arg in &field.args {
+                if !is_input_kind(arg.typ.named_type().kind) {
+                    self.warning(
+                        format!("{}.{}({})", typ.name, field.name, arg.name),
+                        format!(
+                            "An argument cannot be {}",
+                            describe(arg.typ.named_type().kind)
+                        ),
+                    );
+                }
+            }
+        }
+        for input_field in typ.input_fields.iter().flatten() {
+            let kind = input_field.typ.named_type().kind;
+            if !is_input_kind(kind) {
+                self.warning(
+                    format!("{}.{}", typ.name, input_field.name),
+                    format!("An input field cannot be {}", describe(kind)),
+                );
+            }
+        }
+    }
+
+    /// Every interface a type lists must be an interface, and the type must have all of its
+    /// fields and their arguments.
+    fn implementations(&mut self, typ: &schema::FullType) {
+        for interface_ref in typ.interfaces.iter().flatten() {
+            let interface = match self.schema.find_type(interface_ref) {
+                Some(interface) => interface,
+                // A missing type was already reported
+                None => continue,
+            };
+            if interface.kind != Kind::Interface {
+                self.error(
+                    format!("{} (interfaces)", typ.name),
+                    format!(
+                        "`{}` implements `{}`, which is {}",
+                        typ.name,
+                        interface.name,
+                        describe(interface.kind)
+                    ),
+                );
+                continue;
+            }
+
+            for interface_field in interface.fields.iter().flatten() {
+                let path = format!("{}.{}", typ.name, interface_field.name);
+                let field = match typ
+                    .fields
+                    .iter()
+                    .flatten()
+                    .find(|f| f.name == interface_field.name)
+                {
+                    Some(field) => field,
+                    None => {
+                        self.warning(
+                            path,
+                            format!(
+                                "The field required by the interface `{}` is missing",
+                                interface.name
+                            ),
+                        );
+                        continue;
+                    }
+                };
+                for interface_arg in &interface_field.args {
+                    if !field.args.iter().any(|arg| arg.name == interface_arg.name) {
+                        self.warning(
+                            path.clone(),
+                            format!(
+                                "The argument `{}` required by the interface `{}` is missing",
+                                interface_arg.name, interface.name
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn union_members(&mut self, union: &schema::FullType) {
+        for member in union.possible_types.iter().flatten() {
+            if let Some(typ) = self.schema.find_type(member) {
+                if typ.kind != Kind::Object {
+                    self.error(
+                        format!("{} (possible types)", union.name),
+                        format!(
+                            "The union member `{}` is {}, not an object",
+                            typ.name,
+                            describe(typ.kind)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn interface_possible_types(&mut self, interface: &schema::FullType) {
+        for possible_type in interface.possible_types.iter().flatten() {
+            let typ = match self.schema.find_type(possible_type) {
+                Some(typ) => typ,
+                None => continue,
+            };
+            let implements = typ
+                .interfaces
+                .iter()
+                .flatten()
+                .any(|i| i.name.as_ref() == Some(&interface.name));
+            if typ.kind != Kind::Object {
+                self.error(
+                    format!("{} (possible types)", interface.name),
+                    format!(
+                        "The possible type `{}` is {}, not an object",
+                        typ.name,
+                        describe(typ.kind)
+                    ),
+                );
+            } else if !implements {
+                self.warning(
+                    format!("{} (possible types)", interface.name),
+                    format!(
+                        "The possible type `{}` does not implement `{}`",
+                        typ.name, interface.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn is_input_kind(kind: Kind) -> bool {
+    matches!(kind, Kind::Scalar | Kind::Enum | Kind::InputObject)
+}
+
+fn describe(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Object => "an object",
+        Kind::InputObject => "an input object",
+        Kind::Interface => "an interface",
+        Kind::Union => "a union",
+        Kind::Enum => "an enum",
+        Kind::Scalar => "a scalar",
+        Kind::List => "a list",
+        Kind::NonNull => "a non-null type",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() {
+        let named = |kind: &str, name: &str| json!({ "kind": kind, "name": name, "ofType": null });
+        let field = |name: &str, typ: serde_json::Value| {
+            json!({
+                "name": name, "description": null, "args": [], "type": typ,
+                "isDeprecated": false, "deprecationReason": null,
+            })
+        };
+        let typ =
+            |kind: &str, name: &str, fields: serde_json::Value, interfaces, possible_types| {
+                json!({
+                    "kind": kind, "name": name, "description": null,
+                    "fields": fields, "inputFields": null, "enumValues": null,
+                    "interfaces": interfaces, "possibleTypes": possible_types,
+                })
+            };
+
+        let schema: schema::Schema = serde_json::from_value(json!({
+            "queryType": { "name": "Query" },
+            "mutationType": { "name": "Mutation" },
+            "types": [
+                typ("OBJECT", "Query", json!([
+                    field("user", named("OBJECT", "User")),
+                    field("node", named("INTERFACE", "Node")),
+                    field("missing", named("OBJECT", "Missing")),
+                    field("role", named("OBJECT", "Role")),
+                ]), json!([]), json!(null)),
+                typ("INTERFACE", "Node", json!([field("id", named("SCALAR", "ID"))]), json!(null), json!([named("OBJECT", "User")])),
+                typ("OBJECT", "User", json!([]), json!([named("INTERFACE", "Node")]), json!(null)),
+                typ("ENUM", "Role", json!(null), json!(null), json!(null)),
+                typ("UNION", "Result", json!(null), json!(null), json!([named("ENUM", "Role")])),
+                typ("SCALAR", "ID", json!(null), json!(null), json!(null)),
+                typ("SCALAR", "ID", json!(null), json!(null), json!(null)),
+            ],
+        }))
+        .unwrap();
+
+        let diagnostics: Vec<(Severity, String)> = validate(&schema)
+            .into_iter()
+            .map(|d| (d.severity, d.to_string()))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    Severity::Error,
+                    "ID: The type `ID` is defined more than once".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "schema (mutation): The mutation root type `Mutation` does not exist"
+                        .to_string()
+                ),
+                (
+                    Severity::Error,
+                    "Query.missing: The type `Missing` does not exist".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "Query.role: `Role` is referred to as an object but is an enum".to_string()
+                ),
+                (
+                    Severity::Warning,
+                    "User.id: The field required by the interface `Node` is missing".to_string()
+                ),
+                (
+                    Severity::Error,
+                    "Result (possible types): The union member `Role` is an enum, not an object"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+}